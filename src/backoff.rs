@@ -0,0 +1,126 @@
+//!    Rust telegram bot api. The library provides asynchronous access to the telegram bot api.
+//!    Copyright (C) 2024  Andrew Kozmin
+//!
+//!    This program is free software: you can redistribute it and/or modify
+//!    it under the terms of the GNU Affero General Public License as published by
+//!    the Free Software Foundation, either version 3 of the License, or
+//!    (at your option) any later version.
+//!
+//!    This program is distributed in the hope that it will be useful,
+//!    but WITHOUT ANY WARRANTY; without even the implied warranty of
+//!    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//!    GNU Affero General Public License for more details.
+//!
+//!    You should have received a copy of the GNU Affero General Public License
+//!    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+
+use std::time::Duration;
+
+use ring::rand::{SecureRandom, SystemRandom};
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Backoff {
+  pub initial_delay: Duration,
+  pub max_delay: Duration,
+  pub multiplier: f64,
+  pub jitter: bool,
+}
+
+
+impl Backoff {
+  pub fn new(initial_delay: Duration, max_delay: Duration, multiplier: f64, jitter: bool) -> Self {
+    Self {
+      initial_delay,
+      max_delay,
+      multiplier,
+      jitter,
+    }
+  }
+
+
+  /// Returns the delay before the retry number `attempt`, counting from zero.
+  ///
+  /// With jitter enabled the delay is picked uniformly between zero and the exponential delay ("full jitter").
+  pub fn delay(&self, attempt: u32) -> Duration {
+    let exponent: i32 = i32::try_from(attempt).unwrap_or(i32::MAX);
+    let delay: f64 = self.initial_delay.as_secs_f64() * self.multiplier.powi(exponent);
+    let delay: Duration = Duration::try_from_secs_f64(delay).unwrap_or(self.max_delay).min(self.max_delay);
+
+    if !self.jitter {
+      return delay;
+    }
+
+    let mut random: [u8; 4] = [0; 4];
+    match SystemRandom::new().fill(&mut random) {
+      Ok(_) => delay.mul_f64(f64::from(u32::from_le_bytes(random)) / f64::from(u32::MAX)),
+      Err(_) => delay,
+    }
+  }
+
+
+  /// Returns `retry_after` if the server asked to wait that long, like the `retry_after` of a 429 response,
+  /// and the [`delay`](Self::delay) before the retry number `attempt` otherwise.
+  pub fn retry_delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+    retry_after.unwrap_or_else(|| self.delay(attempt))
+  }
+}
+
+
+impl Default for Backoff {
+  fn default() -> Self {
+    Self::new(Duration::from_millis(500), Duration::from_secs(60), 2.0, true)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+
+  fn backoff(jitter: bool) -> Backoff {
+    Backoff::new(Duration::from_millis(500), Duration::from_secs(60), 2.0, jitter)
+  }
+
+
+  #[test]
+  fn delay_grows_exponentially() {
+    let backoff: Backoff = backoff(false);
+    assert_eq!(backoff.delay(0), Duration::from_millis(500));
+    assert_eq!(backoff.delay(1), Duration::from_secs(1));
+    assert_eq!(backoff.delay(2), Duration::from_secs(2));
+    assert_eq!(backoff.delay(6), Duration::from_secs(32));
+  }
+
+
+  #[test]
+  fn delay_is_capped() {
+    let backoff: Backoff = backoff(false);
+    assert_eq!(backoff.delay(7), Duration::from_secs(60));
+    assert_eq!(backoff.delay(1_000), Duration::from_secs(60));
+    assert_eq!(backoff.delay(u32::MAX), Duration::from_secs(60));
+  }
+
+
+  #[test]
+  fn jitter_stays_within_bounds() {
+    let jittered: Backoff = backoff(true);
+    for attempt in [0, 3, 6, 20, u32::MAX] {
+      let limit: Duration = backoff(false).delay(attempt);
+      for _ in 0..100 {
+        assert!(jittered.delay(attempt) <= limit);
+      }
+    }
+  }
+
+
+  #[test]
+  fn retry_after_takes_precedence() {
+    let backoff: Backoff = backoff(false);
+    assert_eq!(backoff.retry_delay(0, Some(Duration::from_secs(5))), Duration::from_secs(5));
+    assert_eq!(backoff.retry_delay(20, Some(Duration::from_secs(120))), Duration::from_secs(120));
+    assert_eq!(backoff.retry_delay(2, None), Duration::from_secs(2));
+  }
+}
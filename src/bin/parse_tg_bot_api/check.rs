@@ -0,0 +1,126 @@
+//!   Rust telegram bot api. The library provides asynchronous access to the telegram bot api.
+//!   Copyright (C) 2024  Andrew Kozmin
+//!
+//!   This program is free software: you can redistribute it and/or modify
+//!   it under the terms of the GNU Affero General Public License as published by
+//!   the Free Software Foundation, either version 3 of the License, or
+//!   (at your option) any later version.
+//!
+//!   This program is distributed in the hope that it will be useful,
+//!   but WITHOUT ANY WARRANTY; without even the implied warranty of
+//!   MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//!   GNU Affero General Public License for more details.
+//!
+//!   You should have received a copy of the GNU Affero General Public License
+//!   along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use anyhow::{bail, Context, Result};
+
+use crate::output;
+
+
+const MANIFEST: &str = r#"[package]
+name = "rtg_bot_api_check"
+version = "0.0.0"
+edition = "2021"
+
+[dependencies]
+serde = { version = "1", features = ["derive"] }
+serde_json = "1"
+
+[features]
+default = ["games", "inline_mode", "payments", "stickers"]
+games = []
+inline_mode = []
+payments = []
+stickers = []
+telegram_passport = []
+"#;
+
+/// Target for the generated `Update` bench, which times itself instead of using the libtest harness.
+const BENCH_TARGET: &str = r#"
+[[bench]]
+name = "update"
+harness = false
+"#;
+
+/// Features that gate generated sections, checked one at a time on top of the combined passes.
+const FEATURES: [&str; 5] = ["games", "inline_mode", "payments", "stickers", "telegram_passport"];
+
+
+/// Builds and tests the generated api in a temporary crate.
+/// The `bench` source is compiled as well and, with `run_bench`, run with its report printed.
+pub(crate) fn check(files: &BTreeMap<String, String>, bench: Option<&str>, run_bench: bool) -> Result<()> {
+  let dir: PathBuf = env::temp_dir().join(format!("rtg_bot_api_check_{}", std::process::id()));
+  let result: Result<()> = check_in(&dir, files, bench, run_bench);
+  fs::remove_dir_all(&dir).ok();
+  result
+}
+
+
+fn check_in(dir: &Path, files: &BTreeMap<String, String>, bench: Option<&str>, run_bench: bool) -> Result<()> {
+  fs::create_dir_all(dir.join("src")).context("ERROR: Failed to create the check crate")?;
+  match bench {
+    Some(bench) => {
+      fs::create_dir_all(dir.join("benches")).context("ERROR: Failed to create the check crate")?;
+      fs::write(dir.join("benches/update.rs"), bench)?;
+      fs::write(dir.join("Cargo.toml"), format!("{MANIFEST}{BENCH_TARGET}"))?;
+    },
+    None => fs::write(dir.join("Cargo.toml"), MANIFEST)?,
+  }
+  fs::write(dir.join("src/lib.rs"), "pub mod api;\n")?;
+  output::write_files(&dir.join("src/api").to_string_lossy(), files)?;
+  if Path::new("Cargo.lock").exists() {
+    fs::copy("Cargo.lock", dir.join("Cargo.lock"))?;
+  }
+
+  let mut passes: Vec<Vec<&str>> = vec![vec!["--all-features"], vec!["--no-default-features"]];
+  passes.extend(FEATURES.iter().map(|feature: &&str| vec!["--no-default-features", "--features", feature]));
+  for pass in passes {
+    check_pass(dir, &pass)?;
+  }
+
+  if bench.is_some() {
+    let arguments: &[&str] = if run_bench { &["bench", "--quiet", "--bench", "update"] } else { &["bench", "--quiet", "--bench", "update", "--no-run"] };
+    let output: Output = cargo(dir, arguments, &[])?;
+    if !output.status.success() {
+      bail!("ERROR: The generated bench does not compile or fails:\n{}{}", String::from_utf8_lossy(&output.stderr), String::from_utf8_lossy(&output.stdout));
+    }
+    if run_bench {
+      print!("{}", String::from_utf8_lossy(&output.stdout));
+    }
+  }
+
+  Ok(())
+}
+
+
+/// Builds the generated api with `features` and runs its round-trip tests.
+fn check_pass(dir: &Path, features: &[&str]) -> Result<()> {
+  let output: Output = cargo(dir, &["test", "--quiet"], features)?;
+  if !output.status.success() {
+    bail!("ERROR: The generated api does not compile or fails its tests with {}:\n{}{}", features.join(" "), String::from_utf8_lossy(&output.stderr), String::from_utf8_lossy(&output.stdout));
+  }
+
+  Ok(())
+}
+
+
+fn cargo(dir: &Path, arguments: &[&str], features: &[&str]) -> Result<Output> {
+  let cargo: String = env::var("CARGO").unwrap_or_else(|_| String::from("cargo"));
+  let target_dir: PathBuf = env::current_dir()?.join("target/api_check");
+  Command::new(cargo)
+    .args(arguments)
+    .args(features)
+    .current_dir(dir)
+    .env("CARGO_TARGET_DIR", target_dir)
+    .output()
+    .context(format!("ERROR: Failed to run cargo {}", arguments[0]))
+}
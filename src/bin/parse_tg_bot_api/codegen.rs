@@ -0,0 +1,1771 @@
+//!   Rust telegram bot api. The library provides asynchronous access to the telegram bot api.
+//!   Copyright (C) 2024  Andrew Kozmin
+//!
+//!   This program is free software: you can redistribute it and/or modify
+//!   it under the terms of the GNU Affero General Public License as published by
+//!   the Free Software Foundation, either version 3 of the License, or
+//!   (at your option) any later version.
+//!
+//!   This program is distributed in the hope that it will be useful,
+//!   but WITHOUT ANY WARRANTY; without even the implied warranty of
+//!   MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//!   GNU Affero General Public License for more details.
+//!
+//!   You should have received a copy of the GNU Affero General Public License
+//!   along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::Write;
+
+use anyhow::Result;
+
+use crate::tg_api::{Type, Method, Field, Parameter, Version};
+
+
+const HEADER: &str = "\
+//!    Rust telegram bot api. The library provides asynchronous access to the telegram bot api.
+//!    Copyright (C) 2024  Andrew Kozmin
+//!
+//!    This program is free software: you can redistribute it and/or modify
+//!    it under the terms of the GNU Affero General Public License as published by
+//!    the Free Software Foundation, either version 3 of the License, or
+//!    (at your option) any later version.
+//!
+//!    This program is distributed in the hope that it will be useful,
+//!    but WITHOUT ANY WARRANTY; without even the implied warranty of
+//!    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//!    GNU Affero General Public License for more details.
+//!
+//!    You should have received a copy of the GNU Affero General Public License
+//!    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//!
+//!    This file is generated by `cargo run --bin parse_tg_bot_api`, do not edit it manually.
+";
+
+const PRIMITIVE_TYPES: [&str; 4] = ["i64", "bool", "f64", "String"];
+
+const UNKNOWN_TYPE: &str = "serde_json::Value";
+
+const UPDATE_TYPE: &str = "Update";
+
+const DISCRIMINATORS: [&str; 3] = ["type", "status", "source"];
+
+const DOCS_URL: &str = "https://core.telegram.org/bots/api";
+
+const SECTION_FEATURES: [(&str, &str); 5] = [
+  ("Stickers", "stickers"),
+  ("Inline mode", "inline_mode"),
+  ("Payments", "payments"),
+  ("Telegram Passport", "telegram_passport"),
+  ("Games", "games"),
+];
+
+const STRING_ENUMS: [StringEnum; 3] = [
+  StringEnum {
+    name: "ParseMode",
+    owners: &[],
+    field: "parse_mode",
+    variants: &[("MarkdownV2", "MarkdownV2"), ("Html", "HTML"), ("Markdown", "Markdown")],
+  },
+  StringEnum {
+    name: "ChatAction",
+    owners: &["sendChatAction"],
+    field: "action",
+    variants: &[
+      ("Typing", "typing"), ("UploadPhoto", "upload_photo"), ("RecordVideo", "record_video"), ("UploadVideo", "upload_video"),
+      ("RecordVoice", "record_voice"), ("UploadVoice", "upload_voice"), ("UploadDocument", "upload_document"),
+      ("ChooseSticker", "choose_sticker"), ("FindLocation", "find_location"), ("RecordVideoNote", "record_video_note"),
+      ("UploadVideoNote", "upload_video_note"),
+    ],
+  },
+  StringEnum {
+    name: "DiceEmoji",
+    owners: &["sendDice", "Dice"],
+    field: "emoji",
+    variants: &[
+      ("Dice", "🎲"), ("Darts", "🎯"), ("Basketball", "🏀"), ("Football", "⚽"), ("Bowling", "🎳"), ("SlotMachine", "🎰"),
+    ],
+  },
+];
+
+/// Update payloads as the Bot API delivers them, by the kind of update they carry.
+const UPDATE_PAYLOADS: [(&str, &str); 3] = [
+  ("message", include_str!("payloads/message_update.json")),
+  ("inline_query", include_str!("payloads/inline_query_update.json")),
+  ("callback_query", include_str!("payloads/callback_query_update.json")),
+];
+
+/// Method responses as the Bot API returns them, including the `ok` envelope.
+const RESPONSE_PAYLOADS: [(&str, &str); 1] = [
+  ("getMe", include_str!("payloads/get_me.json")),
+];
+
+const PRELUDE_TYPES: [&str; 10] = [
+  "Update", "Message", "User", "Chat", "CallbackQuery", "InlineKeyboardMarkup", "InlineKeyboardButton",
+  "ReplyKeyboardMarkup", "KeyboardButton", "InputFile",
+];
+
+const PRELUDE_METHODS: [&str; 8] = [
+  "getMe", "getUpdates", "sendMessage", "sendPhoto", "editMessageText", "deleteMessage", "answerCallbackQuery",
+  "setWebhook",
+];
+
+const KEYWORDS: [&str; 38] = [
+  "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false", "fn",
+  "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self", "Self",
+  "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where", "while",
+];
+
+
+struct Api<'a> {
+  types: BTreeMap<&'a str, &'a Type>,
+  unions: BTreeMap<String, Union>,
+}
+
+
+struct Union {
+  name: String,
+  variants: Vec<String>,
+}
+
+
+struct Member {
+  name: String,
+  r#type: String,
+  required: bool,
+  cfg: Option<String>,
+  description: String,
+  /// The documented fixed value, set by the constructor instead of taken as an argument.
+  value: Option<String>,
+}
+
+
+struct Module {
+  feature: Option<&'static str>,
+  body: String,
+}
+
+
+impl Module {
+  fn new(section: &str) -> Self {
+    Self {
+      feature: section_feature(section),
+      body: String::new(),
+    }
+  }
+}
+
+
+struct StringEnum {
+  name: &'static str,
+  owners: &'static [&'static str],
+  field: &'static str,
+  variants: &'static [(&'static str, &'static str)],
+}
+
+
+pub(crate) fn generate(types: &HashSet<Type>, methods: &HashSet<Method>, versions: &[Version]) -> Result<BTreeMap<String, String>> {
+  let types: BTreeMap<&str, &Type> = deduplicate_types(types);
+  let methods: BTreeMap<&str, &Method> = methods.iter().map(|method: &Method| (method.name.as_str(), method)).collect();
+  let unions: BTreeMap<String, Union> = collect_unions(&types, &methods);
+  let api: Api = Api {
+    types,
+    unions,
+  };
+
+  let mut type_modules: BTreeMap<String, Module> = BTreeMap::new();
+  for r#type in api.types.values() {
+    let module: &mut Module = type_modules.entry(module_name(&r#type.section)).or_insert_with(|| Module::new(&r#type.section));
+    write!(module.body, "\n\n{}\n", generate_type(r#type, &api)?)?;
+  }
+
+  let mut method_modules: BTreeMap<String, Module> = BTreeMap::new();
+  for method in methods.values() {
+    let features: Vec<&str> = method_features(method, &api).into_iter()
+      .filter(|feature: &&str| section_feature(&method.section) != Some(*feature))
+      .collect();
+    let module: &mut Module = method_modules.entry(module_name(&method.section)).or_insert_with(|| Module::new(&method.section));
+    write!(module.body, "\n\n{}\n", gate(&generate_method(method, &api)?, &features))?;
+  }
+
+  let mut result: String = String::from(HEADER);
+  writeln!(result, "\n\n#![allow(deprecated, clippy::too_many_arguments, clippy::large_enum_variant)]\n")?;
+  writeln!(result, "\npub mod methods;\npub mod prelude;\npub mod types;\n")?;
+  if let Some(version) = generate_version(versions)? {
+    writeln!(result, "\n{version}\n")?;
+  }
+  writeln!(result, "\nuse std::collections::BTreeMap;\n\nuse serde::{{de::DeserializeOwned, Deserialize, Serialize}};\n\nuse types::*;\n")?;
+  writeln!(result, "\npub trait Request: Serialize {{\n  const METHOD: &'static str;\n  type Response: DeserializeOwned;\n}}")?;
+
+  for string_enum in &STRING_ENUMS {
+    write!(result, "\n\n{}\n", generate_string_enum(string_enum)?)?;
+  }
+
+  for union in api.unions.values() {
+    write!(result, "\n\n{}\n", generate_union(union, &api)?)?;
+  }
+
+  let true_results: BTreeSet<&str> = methods.values()
+    .filter_map(|method: &&Method| true_result(&method.return_type, &api))
+    .collect();
+  for r#type in true_results {
+    write!(result, "\n\n{}\n", generate_true_result(r#type, &api)?)?;
+  }
+
+  write!(result, "\n\n{}\n", generate_tests(&methods, &api)?)?;
+
+  let mut files: BTreeMap<String, String> = BTreeMap::new();
+  files.insert(String::from("mod.rs"), result);
+  files.insert(String::from("prelude.rs"), generate_prelude(&methods, &api)?);
+  generate_modules("types", &type_modules, &mut files)?;
+  generate_modules("methods", &method_modules, &mut files)?;
+
+  Ok(files)
+}
+
+
+/// Emits `API_VERSION` documented with the changes listed under "Recent changes".
+fn generate_version(versions: &[Version]) -> Result<Option<String>> {
+  let latest: &Version = match versions.first() {
+    Some(latest) => latest,
+    None => return Ok(None),
+  };
+
+  let mut result: String = String::from("/// The Bot API version the bindings were generated from.\n///\n/// # Recent changes\n");
+  for version in versions {
+    writeln!(result, "///\n/// ## Bot API {} ({})\n///\n/// <{DOCS_URL}#{}>\n///", version.name, version.date, version.anchor)?;
+    for change in &version.changes {
+      writeln!(result, "/// - {change}")?;
+    }
+  }
+  write!(result, "pub const API_VERSION: &str = {:?};", latest.name)?;
+
+  Ok(Some(result))
+}
+
+
+fn generate_prelude(methods: &BTreeMap<&str, &Method>, api: &Api) -> Result<String> {
+  let mut result: String = String::from(HEADER);
+  let string_enums: Vec<&str> = STRING_ENUMS.iter().map(|string_enum: &StringEnum| string_enum.name).collect();
+  writeln!(result, "\n\npub use super::{{{}, Request}};", string_enums.join(", "))?;
+
+  let mut items: Vec<(String, String, Vec<&str>)> = Vec::new();
+  for name in PRELUDE_TYPES {
+    if let Some(r#type) = api.types.get(name) {
+      items.push((String::from("types"), name.to_string(), section_feature(&r#type.section).into_iter().collect()));
+      if name == UPDATE_TYPE {
+        items.push((String::from("types"), String::from("UpdateKind"), Vec::new()));
+      }
+    }
+  }
+  for name in PRELUDE_METHODS {
+    if let Some(method) = methods.get(name) {
+      items.push((String::from("methods"), pascal_case(name), method_features(method, api)));
+    }
+  }
+
+  writeln!(result)?;
+  for (module, name, features) in items {
+    if !features.is_empty() {
+      writeln!(result, "{}", cfg(&features))?;
+    }
+    writeln!(result, "pub use super::{module}::{name};")?;
+  }
+
+  Ok(result)
+}
+
+
+fn generate_modules(directory: &str, modules: &BTreeMap<String, Module>, files: &mut BTreeMap<String, String>) -> Result<()> {
+  let mut declarations: String = String::new();
+  let mut exports: String = String::new();
+
+  for (name, module) in modules {
+    let cfg: String = match module.feature {
+      Some(feature) => format!("{}\n", cfg(&[feature])),
+      None => String::new(),
+    };
+    writeln!(declarations, "{cfg}pub mod {name};")?;
+    writeln!(exports, "{cfg}pub use {name}::*;")?;
+
+    files.insert(format!("{directory}/{name}.rs"), format!("{HEADER}\n\nuse super::super::*;\n{}", module.body));
+  }
+
+  files.insert(format!("{directory}/mod.rs"), format!("{HEADER}\n\n{declarations}\n{exports}"));
+
+  Ok(())
+}
+
+
+fn module_name(section: &str) -> String {
+  let words: Vec<String> = section.split(|ch: char| !ch.is_alphanumeric())
+    .filter(|word: &&str| !word.is_empty())
+    .map(str::to_lowercase)
+    .collect();
+
+  if words.is_empty() {
+    return String::from("other");
+  }
+
+  words.join("_")
+}
+
+
+fn method_features(method: &Method, api: &Api) -> Vec<&'static str> {
+  let mut result: Vec<&str> = section_feature(&method.section).into_iter().collect();
+  if let Some(feature) = type_feature(&method.return_type, api) {
+    if !result.contains(&feature) {
+      result.push(feature);
+    }
+  }
+
+  result
+}
+
+
+/// Picks one type per name, preferring the richest definition; ties are broken by comparing the types, so the pick is stable.
+pub(crate) fn deduplicate_types(types: &HashSet<Type>) -> BTreeMap<&str, &Type> {
+  let mut result: BTreeMap<&str, &Type> = BTreeMap::new();
+
+  for r#type in types {
+    match result.get(r#type.name.as_str()) {
+      Some(existing) if (type_priority(existing), *existing) >= (type_priority(r#type), r#type) => (),
+      _ => {
+        result.insert(r#type.name.as_str(), r#type);
+      },
+    }
+  }
+
+  result
+}
+
+
+fn type_priority(r#type: &Type) -> u8 {
+  if !union_variants(r#type).is_empty() {
+    1
+  } else if r#type.fields.is_empty() {
+    0
+  } else {
+    2
+  }
+}
+
+
+fn union_variants(r#type: &Type) -> Vec<&str> {
+  let is_union: bool = r#type.fields.iter().all(|field: &Field| field.name == field.r#type && field.description.is_empty());
+  if !is_union {
+    return Vec::new();
+  }
+
+  r#type.fields.iter()
+    .map(|field: &Field| field.name.as_str())
+    .filter(|name: &&str| is_type_name(name))
+    .collect()
+}
+
+
+fn collect_unions(types: &BTreeMap<&str, &Type>, methods: &BTreeMap<&str, &Method>) -> BTreeMap<String, Union> {
+  let fields = types.values().flat_map(|r#type: &&Type| r#type.fields.iter().map(|field: &Field| (&field.name, &field.r#type)));
+  let parameters = methods.values().flat_map(|method: &&Method| method.parameters.iter().map(|parameter: &Parameter| (&parameter.name, &parameter.r#type)));
+
+  let mut result: BTreeMap<String, Union> = BTreeMap::new();
+  let mut names: HashSet<String> = types.keys().map(|name: &&str| name.to_string()).collect();
+
+  for (name, r#type) in fields.chain(parameters) {
+    let r#type: &str = strip_vec(r#type);
+    if result.contains_key(r#type) {
+      continue;
+    }
+
+    let variants: Vec<String> = match union_type_variants(r#type, types) {
+      Some(variants) => variants,
+      None => continue,
+    };
+
+    let mut union_name: String = pascal_case(name);
+    if names.contains(&union_name) {
+      union_name = variants.join("Or");
+    }
+    names.insert(union_name.clone());
+
+    result.insert(r#type.to_string(), Union {
+      name: union_name,
+      variants,
+    });
+  }
+
+  result
+}
+
+
+fn union_type_variants(r#type: &str, types: &BTreeMap<&str, &Type>) -> Option<Vec<String>> {
+  let variants: Vec<String> = r#type.split(" or ")
+    .flat_map(|part: &str| part.split(", "))
+    .flat_map(|part: &str| part.split(" and "))
+    .map(|part: &str| part.trim().to_string())
+    .collect();
+
+  if variants.len() < 2 || !variants.iter().all(|variant: &String| types.contains_key(variant.as_str())) {
+    return None;
+  }
+
+  Some(variants)
+}
+
+
+fn strip_vec(r#type: &str) -> &str {
+  match r#type.strip_prefix("Vec<").and_then(|inner: &str| inner.strip_suffix('>')) {
+    Some(inner) => strip_vec(inner),
+    None => r#type,
+  }
+}
+
+
+fn is_type_name(name: &str) -> bool {
+  name.chars().next().is_some_and(char::is_uppercase) && name.chars().all(char::is_alphanumeric)
+}
+
+
+fn generate_type(r#type: &Type, api: &Api) -> Result<String> {
+  if r#type.name == UPDATE_TYPE {
+    return generate_update(r#type, api);
+  }
+
+  let mut result: String = String::new();
+  let variants: Vec<&str> = union_variants(r#type);
+
+  write!(result, "{}", doc_comment(&r#type.description, &r#type.anchor))?;
+  if let Some(deprecated) = deprecated(&r#type.description) {
+    writeln!(result, "{deprecated}")?;
+  }
+  if !variants.is_empty() {
+    writeln!(result, "#[derive(Debug, Clone, PartialEq, Serialize)]")?;
+    writeln!(result, "#[serde(untagged)]")?;
+    writeln!(result, "#[non_exhaustive]")?;
+    writeln!(result, "pub enum {} {{", r#type.name)?;
+    for variant in &variants {
+      if let Some(cfg) = member_cfg(&r#type.section, variant, api) {
+        writeln!(result, "  {cfg}")?;
+      }
+      writeln!(result, "  {variant}({}),", boxed_type(variant, &r#type.name, api))?;
+    }
+    writeln!(result, "  Unknown({UNKNOWN_TYPE}),")?;
+    writeln!(result, "}}\n\n")?;
+    write!(result, "{}", generate_union_deserializer(&r#type.name, &r#type.section, &variants, api)?)?;
+    if let Some(accessors) = generate_union_accessors(r#type, &variants, api)? {
+      write!(result, "\n\n\n{accessors}")?;
+    }
+    return Ok(result);
+  }
+
+  let members: Vec<Member> = r#type.fields.iter()
+    .filter(|field: &&Field| field.name != field.r#type || !field.description.is_empty())
+    .map(|field: &Field| Member {
+      name: field_name(&field.name),
+      r#type: boxed_type(&member_type(&r#type.name, &field.name, &field.r#type, api), &r#type.name, api),
+      required: !field.optional,
+      cfg: member_cfg(&r#type.section, &field.r#type, api),
+      description: field.description.clone(),
+      value: fixed_value(field).map(|value: String| fixed_expression(field, &value)),
+    })
+    .collect();
+
+  write!(result, "{}", generate_struct(&r#type.name, &members, true)?)?;
+  if let Some(field) = list_field(r#type) {
+    write!(result, "\n\n\n{}", generate_list_iterator(r#type, field, api)?)?;
+  }
+  Ok(result)
+}
+
+
+/// Finds the only field of a type that just wraps a list, like `transactions` of `StarTransactions`.
+fn list_field(r#type: &Type) -> Option<&Field> {
+  match r#type.fields.as_slice() {
+    [field] if !field.optional && field.r#type.starts_with("Vec<") => Some(field),
+    _ => None,
+  }
+}
+
+
+/// Emits `IntoIterator` for a list type and a reference to it, iterating over its list.
+fn generate_list_iterator(r#type: &Type, field: &Field, api: &Api) -> Result<String> {
+  let mut result: String = String::new();
+  let name: &str = &r#type.name;
+  let list: String = member_type(name, &field.name, &field.r#type, api);
+  let item: &str = list.strip_prefix("Vec<").and_then(|item: &str| item.strip_suffix('>')).unwrap_or(&list);
+  let cfg: String = member_cfg(&r#type.section, &field.r#type, api).map_or_else(String::new, |cfg: String| format!("{cfg}\n"));
+  let field: String = field_name(&field.name);
+
+  writeln!(result, "{cfg}impl IntoIterator for {name} {{")?;
+  writeln!(result, "  type Item = {item};")?;
+  writeln!(result, "  type IntoIter = std::vec::IntoIter<{item}>;\n")?;
+  writeln!(result, "  fn into_iter(self) -> Self::IntoIter {{")?;
+  writeln!(result, "    self.{field}.into_iter()")?;
+  writeln!(result, "  }}")?;
+  writeln!(result, "}}\n\n")?;
+  writeln!(result, "{cfg}impl<'a> IntoIterator for &'a {name} {{")?;
+  writeln!(result, "  type Item = &'a {item};")?;
+  writeln!(result, "  type IntoIter = std::slice::Iter<'a, {item}>;\n")?;
+  writeln!(result, "  fn into_iter(self) -> Self::IntoIter {{")?;
+  writeln!(result, "    self.{field}.iter()")?;
+  writeln!(result, "  }}")?;
+  result.push('}');
+
+  Ok(result)
+}
+
+
+/// Emits a string enum that keeps the values it does not know in `Unknown`, so they serialize back unchanged.
+fn generate_string_enum(string_enum: &StringEnum) -> Result<String> {
+  let mut result: String = String::new();
+  let name: &str = string_enum.name;
+
+  writeln!(result, "#[derive(Debug, Clone, PartialEq, Eq, Hash)]")?;
+  writeln!(result, "#[non_exhaustive]")?;
+  writeln!(result, "pub enum {name} {{")?;
+  for (variant, _) in string_enum.variants {
+    writeln!(result, "  {variant},")?;
+  }
+  writeln!(result, "  Unknown(String),")?;
+  writeln!(result, "}}\n\n")?;
+
+  writeln!(result, "impl {name} {{")?;
+  writeln!(result, "  pub fn as_str(&self) -> &str {{")?;
+  writeln!(result, "    match self {{")?;
+  for (variant, value) in string_enum.variants {
+    writeln!(result, "      Self::{variant} => \"{value}\",")?;
+  }
+  writeln!(result, "      Self::Unknown(value) => value,")?;
+  writeln!(result, "    }}")?;
+  writeln!(result, "  }}")?;
+  writeln!(result, "}}\n\n")?;
+
+  writeln!(result, "impl Serialize for {name} {{")?;
+  writeln!(result, "  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {{")?;
+  writeln!(result, "    serializer.serialize_str(self.as_str())")?;
+  writeln!(result, "  }}")?;
+  writeln!(result, "}}\n\n")?;
+
+  writeln!(result, "impl<'de> Deserialize<'de> for {name} {{")?;
+  writeln!(result, "  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {{")?;
+  writeln!(result, "    let value: String = String::deserialize(deserializer)?;")?;
+  writeln!(result, "    match value.as_str() {{")?;
+  for (variant, value) in string_enum.variants {
+    writeln!(result, "      \"{value}\" => Ok(Self::{variant}),")?;
+  }
+  writeln!(result, "      _ => Ok(Self::Unknown(value)),")?;
+  writeln!(result, "    }}")?;
+  writeln!(result, "  }}")?;
+  result.push('}');
+
+  Ok(result)
+}
+
+
+fn generate_union(union: &Union, api: &Api) -> Result<String> {
+  let mut result: String = String::new();
+
+  writeln!(result, "#[derive(Debug, Clone, PartialEq, Serialize)]")?;
+  writeln!(result, "#[serde(untagged)]")?;
+  writeln!(result, "#[non_exhaustive]")?;
+  writeln!(result, "pub enum {} {{", union.name)?;
+  for variant in &union.variants {
+    if let Some(cfg) = member_cfg("", variant, api) {
+      writeln!(result, "  {cfg}")?;
+    }
+    writeln!(result, "  {variant}({variant}),")?;
+  }
+  writeln!(result, "  Unknown({UNKNOWN_TYPE}),")?;
+  writeln!(result, "}}\n\n")?;
+
+  let variants: Vec<&str> = union.variants.iter().map(String::as_str).collect();
+  writeln!(result, "{}", generate_union_deserializer(&union.name, "", &variants, api)?)?;
+
+  let conversions: Vec<String> = union.variants.iter()
+    .map(|variant: &String| {
+      let conversion: String = format!("impl From<{variant}> for {} {{\n  fn from(value: {variant}) -> Self {{\n    Self::{variant}(value)\n  }}\n}}", union.name);
+      match member_cfg("", variant, api) {
+        Some(cfg) => format!("{cfg}\n{conversion}"),
+        None => conversion,
+      }
+    })
+    .collect();
+  write!(result, "\n\n{}", conversions.join("\n\n\n"))?;
+
+  Ok(result)
+}
+
+
+/// Decodes a union by the fixed value its variants document, like `status` "always “creator”" or `date` "Always 0".
+/// Variants without one are tried in the documented order.
+fn generate_union_deserializer(name: &str, section: &str, variants: &[&str], api: &Api) -> Result<String> {
+  let mut tagged: String = String::new();
+  let mut untagged: String = String::new();
+
+  for variant in variants {
+    let cfg: String = match member_cfg(section, variant, api) {
+      Some(cfg) => format!("    {cfg}\n"),
+      None => String::new(),
+    };
+    let conditions: Vec<String> = api.types.get(variant).into_iter()
+      .flat_map(|r#type: &&Type| &r#type.fields)
+      .filter_map(|field: &Field| {
+        let value: String = fixed_value(field)?;
+        Some(format!("value.get(\"{}\").is_some_and(|field: &{UNKNOWN_TYPE}| *field == {value})", field.name))
+      })
+      .collect();
+
+    if conditions.is_empty() {
+      write!(untagged, "{cfg}    if let Ok(variant) = serde_json::from_value(value.clone()) {{\n      return Ok(Self::{variant}(variant));\n    }}\n")?;
+    } else {
+      write!(tagged, "{cfg}    if {} {{\n      if let Ok(variant) = serde_json::from_value(value.clone()) {{\n        return Ok(Self::{variant}(variant));\n      }}\n    }}\n", conditions.join(" && "))?;
+    }
+  }
+
+  let mut result: String = String::new();
+  writeln!(result, "impl<'de> Deserialize<'de> for {name} {{")?;
+  writeln!(result, "  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {{")?;
+  writeln!(result, "    let value: {UNKNOWN_TYPE} = {UNKNOWN_TYPE}::deserialize(deserializer)?;")?;
+  write!(result, "{tagged}{untagged}")?;
+  writeln!(result, "    Ok(Self::Unknown(value))")?;
+  writeln!(result, "  }}")?;
+  result.push('}');
+
+  Ok(result)
+}
+
+
+/// Emits getters for the fields every variant of a union has, like `chat` of `MaybeInaccessibleMessage`.
+fn generate_union_accessors(r#type: &Type, variants: &[&str], api: &Api) -> Result<Option<String>> {
+  let accessors: Vec<(&Field, String)> = union_fields(r#type, api);
+  if accessors.is_empty() {
+    return Ok(None);
+  }
+
+  let mut result: String = String::new();
+  writeln!(result, "impl {} {{", r#type.name)?;
+  for (i, (field, field_type)) in accessors.iter().enumerate() {
+    if i != 0 {
+      writeln!(result)?;
+    }
+    writeln!(result, "  pub fn {}(&self) -> Option<&{field_type}> {{", field_name(&field.name))?;
+    writeln!(result, "    match self {{")?;
+    for variant in variants {
+      if let Some(cfg) = member_cfg(&r#type.section, variant, api) {
+        writeln!(result, "      {cfg}")?;
+      }
+      writeln!(result, "      Self::{variant}(value) => Some(&value.{}),", field_name(&field.name))?;
+    }
+    writeln!(result, "      Self::Unknown(_) => None,")?;
+    writeln!(result, "    }}")?;
+    writeln!(result, "  }}")?;
+  }
+  result.push('}');
+
+  Ok(Some(result))
+}
+
+
+/// Finds the required fields all variants of a union share with the same unboxed type, with that type.
+fn union_fields<'a>(r#type: &Type, api: &Api<'a>) -> Vec<(&'a Field, String)> {
+  let variants: Vec<&Type> = match union_variants(r#type).iter().map(|variant: &&str| api.types.get(variant).copied()).collect() {
+    Some(variants) => variants,
+    None => return Vec::new(),
+  };
+  let first: &Type = match variants.first() {
+    Some(first) => first,
+    None => return Vec::new(),
+  };
+
+  first.fields.iter()
+    .filter(|field: &&Field| !field.optional && fixed_value(field).is_none() && member_cfg(&first.section, &field.r#type, api).is_none())
+    .filter_map(|field: &Field| {
+      let field_type: String = member_type(&first.name, &field.name, &field.r#type, api);
+      let is_shared: bool = variants.iter().all(|variant: &&Type| {
+        variant.fields.iter().any(|other: &Field| {
+          let is_boxed: bool = boxed_type(&field_type, &variant.name, api) != field_type;
+          other.name == field.name && !other.optional && !is_boxed && member_type(&variant.name, &other.name, &other.r#type, api) == field_type
+        })
+      });
+      is_shared.then_some((field, field_type))
+    })
+    .collect()
+}
+
+
+/// Finds the value a discriminating field is documented to always have, as a literal.
+fn fixed_value(field: &Field) -> Option<String> {
+  if field.optional {
+    return None;
+  }
+
+  let description: String = normalize_description(&field.description);
+  let lowercase: String = description.to_lowercase();
+  match field.r#type.as_str() {
+    "String" if DISCRIMINATORS.contains(&field.name.as_str()) => {
+      let start: usize = ["always ", "must be "].iter()
+        .find_map(|phrase: &&str| lowercase.find(phrase).map(|start: usize| start + phrase.len()))?;
+      let value: &str = description[start..].trim_start_matches(['“', '"']);
+      let value: &str = value.split(['”', '"', ',', '.', ';', ' ']).next()?;
+      let is_identifier: bool = !value.is_empty() && value.chars().all(|ch: char| ch.is_ascii_alphanumeric() || ch == '_');
+      is_identifier.then(|| format!("{value:?}"))
+    },
+    "i64" => {
+      let value: &str = lowercase.strip_prefix("always ")?.split(['.', ',', ' ']).next()?;
+      value.parse::<i64>().ok().map(|value: i64| value.to_string())
+    },
+    _ => None,
+  }
+}
+
+
+fn fixed_expression(field: &Field, value: &str) -> String {
+  match field.r#type.as_str() {
+    "String" => format!("String::from({value})"),
+    _ => value.to_string(),
+  }
+}
+
+
+/// Emits the result of methods that return either `type` or `True`, like the edit methods.
+fn generate_true_result(r#type: &str, api: &Api) -> Result<String> {
+  let mut result: String = String::new();
+
+  writeln!(result, "/// Either the resulting [`{type}`], or `true` when there is no {type} to return.")?;
+  writeln!(result, "#[derive(Debug, Clone, PartialEq, Serialize)]")?;
+  writeln!(result, "#[serde(untagged)]")?;
+  writeln!(result, "pub enum {type}OrTrue {{")?;
+  writeln!(result, "  {type}({type}),")?;
+  writeln!(result, "  True(bool),")?;
+  writeln!(result, "}}\n\n")?;
+
+  writeln!(result, "impl<'de> Deserialize<'de> for {type}OrTrue {{")?;
+  writeln!(result, "  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {{")?;
+  writeln!(result, "    match {UNKNOWN_TYPE}::deserialize(deserializer)? {{")?;
+  writeln!(result, "      {UNKNOWN_TYPE}::Bool(value) => Ok(Self::True(value)),")?;
+  writeln!(result, "      value => serde_json::from_value(value).map(Self::{type}).map_err(serde::de::Error::custom),")?;
+  writeln!(result, "    }}")?;
+  writeln!(result, "  }}")?;
+  write!(result, "}}")?;
+
+  Ok(gate(&result, &Vec::from_iter(type_feature(r#type, api))))
+}
+
+
+/// Finds the type in a "X or True" return type.
+fn true_result<'a>(return_type: &'a str, api: &Api) -> Option<&'a str> {
+  return_type.strip_suffix(" or True").filter(|r#type: &&str| api.types.contains_key(*r#type))
+}
+
+
+fn generate_update(update: &Type, api: &Api) -> Result<String> {
+  let mut result: String = String::new();
+  let kinds: Vec<&Field> = update.fields.iter().filter(|field: &&Field| field.optional).collect();
+  let fields: Vec<&Field> = update.fields.iter().filter(|field: &&Field| !field.optional).collect();
+
+  write!(result, "{}", doc_comment(&update.description, &update.anchor))?;
+  writeln!(result, "#[derive(Debug, Clone, PartialEq, Serialize)]")?;
+  writeln!(result, "#[non_exhaustive]")?;
+  writeln!(result, "pub struct {UPDATE_TYPE} {{")?;
+  for field in &fields {
+    if !field.description.is_empty() {
+      writeln!(result, "  /// {}", normalize_description(&field.description))?;
+    }
+    writeln!(result, "  pub {}: {},", field_name(&field.name), rust_type(&field.r#type, api))?;
+  }
+  writeln!(result, "  #[serde(flatten)]")?;
+  writeln!(result, "  pub kind: UpdateKind,")?;
+  writeln!(result, "}}\n\n")?;
+
+  writeln!(result, "{}\n\n", generate_update_deserializer(update, &fields, &kinds, api)?)?;
+
+  writeln!(result, "#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]")?;
+  writeln!(result, "#[serde(rename_all = \"snake_case\")]")?;
+  writeln!(result, "#[non_exhaustive]")?;
+  writeln!(result, "pub enum UpdateKind {{")?;
+  for kind in &kinds {
+    if !kind.description.is_empty() {
+      writeln!(result, "  /// {}", normalize_description(kind.description.trim_start_matches("Optional.")))?;
+    }
+    if let Some(deprecated) = deprecated(&kind.description) {
+      writeln!(result, "  {deprecated}")?;
+    }
+    if let Some(cfg) = member_cfg(&update.section, &kind.r#type, api) {
+      writeln!(result, "  {cfg}")?;
+    }
+    writeln!(result, "  {}({}),", pascal_case(&kind.name), rust_type(&kind.r#type, api))?;
+  }
+  writeln!(result, "  #[serde(untagged)]")?;
+  writeln!(result, "  Unknown({UNKNOWN_TYPE}),")?;
+  writeln!(result, "}}\n\n")?;
+
+  writeln!(result, "impl {UPDATE_TYPE} {{")?;
+  write!(result, "{}\n\n", generate_update_accessor("chat", &["chat"], "Chat", true, &kinds, api)?)?;
+  write!(result, "{}\n\n", generate_update_accessor("from", &["from", "user"], "User", false, &kinds, api)?)?;
+
+  writeln!(result, "  pub fn kind_name(&self) -> &'static str {{")?;
+  writeln!(result, "    match self.kind {{")?;
+  for kind in &kinds {
+    if let Some(cfg) = member_cfg(&update.section, &kind.r#type, api) {
+      writeln!(result, "      {cfg}")?;
+    }
+    writeln!(result, "      UpdateKind::{}(_) => \"{}\",", pascal_case(&kind.name), kind.name)?;
+  }
+  writeln!(result, "      UpdateKind::Unknown(_) => \"unknown\",")?;
+  writeln!(result, "    }}")?;
+  writeln!(result, "  }}")?;
+  result.push('}');
+
+  Ok(result)
+}
+
+
+fn generate_update_deserializer(update: &Type, fields: &[&Field], kinds: &[&Field], api: &Api) -> Result<String> {
+  let mut result: String = String::new();
+
+  writeln!(result, "impl<'de> Deserialize<'de> for {UPDATE_TYPE} {{")?;
+  writeln!(result, "  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {{")?;
+  writeln!(result, "    deserializer.deserialize_map(UpdateVisitor)")?;
+  writeln!(result, "  }}")?;
+  writeln!(result, "}}\n\n")?;
+
+  writeln!(result, "struct UpdateVisitor;\n\n")?;
+
+  writeln!(result, "impl<'de> serde::de::Visitor<'de> for UpdateVisitor {{")?;
+  writeln!(result, "  type Value = {UPDATE_TYPE};\n")?;
+  writeln!(result, "  fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {{")?;
+  writeln!(result, "    formatter.write_str(\"a telegram update\")")?;
+  writeln!(result, "  }}\n")?;
+  writeln!(result, "  fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {{")?;
+  for field in fields {
+    writeln!(result, "    let mut {}: Option<{}> = None;", field_name(&field.name), rust_type(&field.r#type, api))?;
+  }
+  writeln!(result, "    let mut kind: Option<UpdateKind> = None;")?;
+  writeln!(result, "    let mut unknown: serde_json::Map<String, {UNKNOWN_TYPE}> = serde_json::Map::new();\n")?;
+  writeln!(result, "    while let Some(key) = map.next_key::<String>()? {{")?;
+  writeln!(result, "      match key.as_str() {{")?;
+  for field in fields {
+    writeln!(result, "        \"{}\" => {} = Some(map.next_value()?),", field.name, field_name(&field.name))?;
+  }
+  for kind in kinds {
+    if let Some(cfg) = member_cfg(&update.section, &kind.r#type, api) {
+      writeln!(result, "        {cfg}")?;
+    }
+    writeln!(result, "        \"{}\" if kind.is_none() => kind = Some(UpdateKind::{}(map.next_value()?)),", kind.name, pascal_case(&kind.name))?;
+  }
+  writeln!(result, "        _ => {{")?;
+  writeln!(result, "          unknown.insert(key, map.next_value()?);")?;
+  writeln!(result, "        }},")?;
+  writeln!(result, "      }}")?;
+  writeln!(result, "    }}\n")?;
+  writeln!(result, "    Ok({UPDATE_TYPE} {{")?;
+  for field in fields {
+    let name: String = field_name(&field.name);
+    writeln!(result, "      {name}: {name}.ok_or_else(|| serde::de::Error::missing_field(\"{}\"))?,", field.name)?;
+  }
+  writeln!(result, "      kind: kind.unwrap_or(UpdateKind::Unknown({UNKNOWN_TYPE}::Object(unknown))),")?;
+  writeln!(result, "    }})")?;
+  writeln!(result, "  }}")?;
+  result.push('}');
+
+  Ok(result)
+}
+
+
+/// Emits a getter for the `field_type` field named one of `field_names` of any update kind.
+/// With `through_message`, kinds without such a field fall back to the one of their `message`, like callback queries.
+fn generate_update_accessor(name: &str, field_names: &[&str], field_type: &str, through_message: bool, kinds: &[&Field], api: &Api) -> Result<String> {
+  let mut result: String = String::new();
+
+  writeln!(result, "  pub fn {name}(&self) -> Option<&{field_type}> {{")?;
+  writeln!(result, "    match &self.kind {{")?;
+  for kind in kinds {
+    let kind_type: Option<&Type> = api.types.get(kind.r#type.as_str()).copied();
+    let value: Option<String> = match kind_type.and_then(|kind_type: &Type| find_field(kind_type, field_names, field_type)) {
+      Some(field) => Some(field_access("value", &kind.r#type, field, api)),
+      None if through_message => kind_type.and_then(|kind_type: &Type| message_access(kind_type, field_names, field_type, api)),
+      None => None,
+    };
+
+    let binding: &str = if value.is_some() { "value" } else { "_" };
+    if let Some(cfg) = member_cfg(&api.types[UPDATE_TYPE].section, &kind.r#type, api) {
+      writeln!(result, "      {cfg}")?;
+    }
+    writeln!(result, "      UpdateKind::{}({binding}) => {},", pascal_case(&kind.name), value.as_deref().unwrap_or("None"))?;
+  }
+  writeln!(result, "      UpdateKind::Unknown(_) => None,")?;
+  writeln!(result, "    }}")?;
+  write!(result, "  }}")?;
+
+  Ok(result)
+}
+
+
+fn find_field<'a>(r#type: &'a Type, field_names: &[&str], field_type: &str) -> Option<&'a Field> {
+  field_names.iter().find_map(|field_name: &&str| {
+    r#type.fields.iter().find(|field: &&Field| field.name == *field_name && field.r#type == field_type)
+  })
+}
+
+
+/// Reads `field` of the `owner` bound to `binding` as an `Option` of a reference.
+fn field_access(binding: &str, owner: &str, field: &Field, api: &Api) -> String {
+  let name: String = field_name(&field.name);
+  let is_boxed: bool = boxed_type(&field.r#type, owner, api) != field.r#type;
+  match (field.optional, is_boxed) {
+    (false, false) => format!("Some(&{binding}.{name})"),
+    (false, true) => format!("Some(&*{binding}.{name})"),
+    (true, false) => format!("{binding}.{name}.as_ref()"),
+    (true, true) => format!("{binding}.{name}.as_deref()"),
+  }
+}
+
+
+/// Reads the field through the `message` of `owner`, which is either a message type or a union with a getter for it.
+fn message_access(owner: &Type, field_names: &[&str], field_type: &str, api: &Api) -> Option<String> {
+  let message: &Field = owner.fields.iter().find(|field: &&Field| field.name == "message")?;
+  let message_type: &Type = api.types.get(message.r#type.as_str())?;
+  let message_value: String = field_access("value", &owner.name, message, api);
+
+  if let Some(field) = find_field(message_type, field_names, field_type) {
+    return Some(format!("{message_value}.and_then(|message: &{}| {})", message_type.name, field_access("message", &message_type.name, field, api)));
+  }
+
+  let (field, _): (&Field, String) = union_fields(message_type, api).into_iter()
+    .find(|(field, r#type): &(&Field, String)| field_names.contains(&field.name.as_str()) && r#type == field_type)?;
+  Some(format!("{message_value}.and_then({}::{})", message_type.name, field_name(&field.name)))
+}
+
+
+fn generate_method(method: &Method, api: &Api) -> Result<String> {
+  let mut result: String = String::new();
+  let struct_name: String = pascal_case(&method.name);
+
+  let members: Vec<Member> = method.parameters.iter()
+    .map(|parameter: &Parameter| Member {
+      name: field_name(&parameter.name),
+      r#type: member_type(&method.name, &parameter.name, &parameter.r#type, api),
+      required: parameter.required,
+      cfg: member_cfg(&method.section, &parameter.r#type, api),
+      description: parameter.description.clone(),
+      value: None,
+    })
+    .collect();
+
+  write!(result, "{}", doc_comment(&method.description, &method.anchor))?;
+  if let Some(deprecated) = deprecated(&method.description) {
+    writeln!(result, "{deprecated}")?;
+  }
+  writeln!(result, "{}\n\n", generate_struct(&struct_name, &members, false)?)?;
+
+  writeln!(result, "impl Request for {struct_name} {{")?;
+  writeln!(result, "  const METHOD: &'static str = \"{}\";", method.name)?;
+  writeln!(result, "  type Response = {};", rust_type(&method.return_type, api))?;
+  result.push('}');
+
+  if let Some(list) = paged_list(method, api) {
+    write!(result, "\n\n\n{}", generate_next_page(method, &struct_name, list)?)?;
+  }
+
+  Ok(result)
+}
+
+
+/// Finds the list field of the result of a method that pages through it with the optional `offset` and `limit` parameters.
+fn paged_list<'a>(method: &Method, api: &Api<'a>) -> Option<&'a Field> {
+  let is_paging = |name: &str| -> bool {
+    method.parameters.iter().any(|parameter: &Parameter| parameter.name == name && !parameter.required && parameter.r#type == "i64")
+  };
+  if !is_paging("offset") || !is_paging("limit") {
+    return None;
+  }
+
+  api.types.get(method.return_type.as_str()).and_then(|r#type: &&Type| list_field(r#type))
+}
+
+
+/// Emits `next_page`, which turns a request into the one for the page after its response.
+fn generate_next_page(method: &Method, struct_name: &str, list: &Field) -> Result<String> {
+  let mut result: String = String::new();
+  let last: &str = match method.parameters.iter().find(|parameter: &&Parameter| parameter.name == "limit") {
+    Some(parameter) if default_value(&parameter.r#type, &parameter.description).is_some() => "received < self.limit.unwrap_or(Self::DEFAULT_LIMIT)",
+    _ => "self.limit.is_some_and(|limit: i64| received < limit)",
+  };
+
+  writeln!(result, "impl {struct_name} {{")?;
+  writeln!(result, "  /// Returns the request for the page after `page`, the response to this request, or `None` if `page` was the last one.")?;
+  writeln!(result, "  pub fn next_page(&self, page: &{}) -> Option<Self> {{", method.return_type)?;
+  writeln!(result, "    let received: i64 = page.{}.len() as i64;", field_name(&list.name))?;
+  writeln!(result, "    if received == 0 || {last} {{")?;
+  writeln!(result, "      return None;")?;
+  writeln!(result, "    }}\n")?;
+  writeln!(result, "    let mut result: Self = self.clone();")?;
+  writeln!(result, "    result.offset = Some(self.offset.unwrap_or(0) + received);")?;
+  writeln!(result, "    Some(result)")?;
+  writeln!(result, "  }}")?;
+  result.push('}');
+
+  Ok(result)
+}
+
+
+fn generate_struct(name: &str, members: &[Member], extra: bool) -> Result<String> {
+  let mut result: String = String::new();
+  let mut body: String = String::new();
+  let mut arguments: Vec<String> = Vec::new();
+  let mut constructor_body: String = String::new();
+  let mut setters: Vec<String> = Vec::new();
+  let mut constants: Vec<String> = Vec::new();
+
+  for member in members {
+    let Member { name, r#type, required, cfg, description, value } = member;
+
+    if !description.is_empty() {
+      writeln!(body, "  /// {}", normalize_description(description))?;
+    }
+    if let Some(deprecated) = deprecated(description) {
+      writeln!(body, "  {deprecated}")?;
+    }
+    if let Some(cfg) = cfg {
+      writeln!(body, "  {cfg}")?;
+      writeln!(constructor_body, "      {cfg}")?;
+    }
+
+    if let (true, Some(value)) = (required, value) {
+      writeln!(body, "  pub {name}: {type},")?;
+      writeln!(constructor_body, "      {name}: {value},")?;
+    } else if *required {
+      writeln!(body, "  pub {name}: {type},")?;
+      match cfg {
+        Some(cfg) => arguments.push(format!("{cfg} {name}: impl Into<{type}>")),
+        None => arguments.push(format!("{name}: impl Into<{type}>")),
+      }
+      writeln!(constructor_body, "      {name}: {name}.into(),")?;
+    } else {
+      writeln!(body, "  #[serde(skip_serializing_if = \"Option::is_none\")]")?;
+      writeln!(body, "  pub {name}: Option<{type}>,")?;
+      match default_value(r#type, description) {
+        Some((const_type, default)) => {
+          let field: &str = name.trim_start_matches("r#");
+          let constant: String = format!("DEFAULT_{}", field.to_uppercase());
+          constants.push(format!("  /// The value Telegram uses when `{field}` is not set.\n  pub const {constant}: {const_type} = {default};"));
+          match const_type {
+            "&str" => writeln!(constructor_body, "      {name}: Some(String::from(Self::{constant})),")?,
+            _ => writeln!(constructor_body, "      {name}: Some(Self::{constant}),")?,
+          }
+        },
+        None => writeln!(constructor_body, "      {name}: None,")?,
+      }
+      let mut setter: String = format!("  pub fn {name}(mut self, {name}: impl Into<{type}>) -> Self {{\n    self.{name} = Some({name}.into());\n    self\n  }}");
+      if let Some(deprecated) = deprecated(description) {
+        setter = format!("  {deprecated}\n{setter}");
+      }
+      match cfg {
+        Some(cfg) => setters.push(format!("  {cfg}\n{setter}")),
+        None => setters.push(setter),
+      }
+    }
+  }
+
+  if extra {
+    writeln!(body, "  #[serde(flatten)]")?;
+    writeln!(body, "  pub extra: BTreeMap<String, {UNKNOWN_TYPE}>,")?;
+    writeln!(constructor_body, "      extra: BTreeMap::new(),")?;
+  }
+
+  writeln!(result, "#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]")?;
+  writeln!(result, "#[non_exhaustive]")?;
+  writeln!(result, "{}\n\n", struct_definition(name, &body))?;
+
+  writeln!(result, "impl {name} {{")?;
+  for constant in constants {
+    writeln!(result, "{constant}\n")?;
+  }
+  writeln!(result, "  pub fn new({}) -> Self {{", arguments.join(", "))?;
+  if constructor_body.is_empty() {
+    writeln!(result, "    Self {{}}")?;
+  } else {
+    writeln!(result, "    Self {{\n{constructor_body}    }}")?;
+  }
+  writeln!(result, "  }}")?;
+  for setter in setters {
+    writeln!(result, "\n{setter}")?;
+  }
+  result.push('}');
+
+  // Built from `new()` so that documented defaults and fixed values are filled in, which a derived `Default` would leave empty.
+  if arguments.is_empty() {
+    write!(result, "\n\n\nimpl Default for {name} {{\n  fn default() -> Self {{\n    Self::new()\n  }}\n}}")?;
+  }
+
+  Ok(result)
+}
+
+
+/// Turns a "Defaults to X" phrase of a description into a constant type and value for a field of type `type`.
+fn default_value(r#type: &str, description: &str) -> Option<(&'static str, String)> {
+  const PHRASE: &str = "defaults to ";
+
+  let description: String = normalize_description(description);
+  let start: usize = description.char_indices()
+    .map(|(index, _): (usize, char)| index)
+    .find(|index: &usize| description[*index..].get(..PHRASE.len()).is_some_and(|phrase: &str| phrase.eq_ignore_ascii_case(PHRASE)))?
+    + PHRASE.len();
+  let value: &str = description[start..].trim_start_matches(['“', '"']);
+  let value: &str = value.split(['”', '"', ',', ';', ' ']).next()?;
+  let value: &str = value.strip_suffix('.').unwrap_or(value);
+
+  match r#type {
+    "i64" => value.parse::<i64>().ok().map(|value: i64| ("i64", value.to_string())),
+    "f64" => value.parse::<f64>().ok().map(|value: f64| ("f64", format!("{value:?}"))),
+    "bool" => match value.to_lowercase().as_str() {
+      "true" => Some(("bool", String::from("true"))),
+      "false" => Some(("bool", String::from("false"))),
+      _ => None,
+    },
+    "String" if !value.is_empty() && description[start..].starts_with(['“', '"']) => Some(("&str", format!("{value:?}"))),
+    _ => None,
+  }
+}
+
+
+fn struct_definition(name: &str, body: &str) -> String {
+  if body.is_empty() {
+    return format!("pub struct {name} {{}}");
+  }
+
+  format!("pub struct {name} {{\n{body}}}")
+}
+
+
+fn generate_tests(methods: &BTreeMap<&str, &Method>, api: &Api) -> Result<String> {
+  let mut tests: Vec<String> = Vec::new();
+
+  for r#type in api.types.values() {
+    let variants: Vec<&str> = union_variants(r#type);
+    if variants.is_empty() {
+      let mut features: BTreeSet<&str> = BTreeSet::new();
+      if let Some(value) = sample(&r#type.name, api, &mut features, &mut Vec::new()) {
+        tests.push(gate(&round_trip_test(&r#type.name, &value), &Vec::from_iter(features)));
+      }
+      continue;
+    }
+
+    for variant in variants {
+      let mut features: BTreeSet<&str> = section_feature(&r#type.section).into_iter().collect();
+      if let Some(value) = sample(variant, api, &mut features, &mut vec![r#type.name.clone()]) {
+        let value: String = if boxed_type(variant, &r#type.name, api) == variant {
+          format!("{}::{variant}({value})", r#type.name)
+        } else {
+          format!("{}::{variant}(Box::new({value}))", r#type.name)
+        };
+        tests.push(gate(&round_trip_test(&format!("{}{variant}", r#type.name), &value), &Vec::from_iter(features)));
+      }
+    }
+  }
+
+  for string_enum in &STRING_ENUMS {
+    let mut test: String = String::new();
+    writeln!(test, "  #[test]")?;
+    writeln!(test, "  fn {}_unknown_round_trip() {{", snake_case(string_enum.name))?;
+    writeln!(test, "    round_trip({}::{});", string_enum.name, string_enum.variants[0].0)?;
+    writeln!(test, "    round_trip({}::Unknown(String::from(\"🤡\")));", string_enum.name)?;
+    writeln!(test, "    assert_eq!(serde_json::to_string(&{}::Unknown(String::from(\"🤡\"))).unwrap(), \"\\\"🤡\\\"\");", string_enum.name)?;
+    write!(test, "  }}")?;
+    tests.push(test);
+  }
+
+  for union in api.unions.values() {
+    for variant in &union.variants {
+      let mut features: BTreeSet<&str> = BTreeSet::new();
+      if let Some(value) = sample(variant, api, &mut features, &mut Vec::new()) {
+        let value: String = format!("{}::{variant}({value})", union.name);
+        tests.push(gate(&round_trip_test(&format!("{}{variant}", union.name), &value), &Vec::from_iter(features)));
+      }
+    }
+  }
+
+  for method in methods.values() {
+    let mut features: BTreeSet<&str> = method_features(method, api).into_iter().collect();
+    let arguments: Option<Vec<String>> = method.parameters.iter()
+      .filter(|parameter: &&Parameter| parameter.required)
+      .map(|parameter: &Parameter| sample(&member_type(&method.name, &parameter.name, &parameter.r#type, api), api, &mut features, &mut Vec::new()))
+      .collect();
+
+    if let Some(arguments) = arguments {
+      let value: String = format!("{}::new({})", pascal_case(&method.name), arguments.join(", "));
+      tests.push(gate(&round_trip_test(&method.name, &value), &Vec::from_iter(features.clone())));
+
+      if let Some(page) = paged_list(method, api).and(sample(&method.return_type, api, &mut features, &mut Vec::new())) {
+        let struct_name: String = pascal_case(&method.name);
+        let mut test: String = String::new();
+        writeln!(test, "  #[test]")?;
+        writeln!(test, "  fn {}_next_page() {{", snake_case(&method.name))?;
+        writeln!(test, "    let page: {} = {page};", method.return_type)?;
+        writeln!(test, "    let request: {struct_name} = {value}.limit(1);")?;
+        writeln!(test, "    assert_eq!((&page).into_iter().count(), 1);")?;
+        writeln!(test, "    assert_eq!(request.next_page(&page).and_then(|next: {struct_name}| next.offset), Some(1));")?;
+        writeln!(test, "    assert_eq!(request.limit(2).next_page(&page), None);")?;
+        write!(test, "  }}")?;
+        tests.push(gate(&test, &Vec::from_iter(features)));
+      }
+    }
+  }
+
+  let true_results: BTreeSet<&str> = methods.values()
+    .filter_map(|method: &&Method| true_result(&method.return_type, api))
+    .collect();
+  for r#type in true_results {
+    let mut features: BTreeSet<&str> = BTreeSet::new();
+    if let Some(value) = sample(r#type, api, &mut features, &mut Vec::new()) {
+      let mut test: String = String::new();
+      writeln!(test, "  #[test]")?;
+      writeln!(test, "  fn {}_or_true_round_trip() {{", snake_case(r#type))?;
+      writeln!(test, "    round_trip({type}OrTrue::{type}({value}));")?;
+      writeln!(test, "    round_trip({type}OrTrue::True(true));")?;
+      writeln!(test, "    assert_eq!(serde_json::from_str::<{type}OrTrue>(\"true\").unwrap(), {type}OrTrue::True(true));")?;
+      write!(test, "  }}")?;
+      tests.push(gate(&test, &Vec::from_iter(features)));
+    }
+  }
+
+  if let Some(update) = api.types.get(UPDATE_TYPE) {
+    for (kind, payload) in UPDATE_PAYLOADS {
+      let field: &Field = match update.fields.iter().find(|field: &&Field| field.name == kind) {
+        Some(field) => field,
+        None => continue,
+      };
+
+      let mut test: String = String::new();
+      writeln!(test, "  #[test]")?;
+      writeln!(test, "  fn {kind}_update_payload() {{")?;
+      writeln!(test, "    let payload: {UNKNOWN_TYPE} = serde_json::from_str(r#\"{}\"#).unwrap();", payload.trim())?;
+      writeln!(test, "    let update: {UPDATE_TYPE} = serde_json::from_value(payload.clone()).unwrap();")?;
+      writeln!(test, "    assert_eq!(update.kind_name(), \"{kind}\");")?;
+      writeln!(test, "    assert_eq!(serde_json::to_value(&update).unwrap(), payload);")?;
+      writeln!(test, "    let object: {} = serde_json::from_value(payload[\"{kind}\"].clone()).unwrap();", rust_type(&field.r#type, api))?;
+      writeln!(test, "    assert_eq!(serde_json::to_value(&object).unwrap(), payload[\"{kind}\"]);")?;
+      write!(test, "  }}")?;
+      tests.push(gate(&test, &Vec::from_iter(type_feature(&field.r#type, api))));
+    }
+  }
+
+  for (name, payload) in RESPONSE_PAYLOADS {
+    let method: &Method = match methods.get(name) {
+      Some(method) => method,
+      None => continue,
+    };
+
+    let mut test: String = String::new();
+    writeln!(test, "  #[test]")?;
+    writeln!(test, "  fn {}_response_payload() {{", snake_case(name))?;
+    writeln!(test, "    let payload: {UNKNOWN_TYPE} = serde_json::from_str(r#\"{}\"#).unwrap();", payload.trim())?;
+    writeln!(test, "    assert_eq!(payload[\"ok\"], true);")?;
+    writeln!(test, "    let result: <{} as Request>::Response = serde_json::from_value(payload[\"result\"].clone()).unwrap();", pascal_case(name))?;
+    writeln!(test, "    assert_eq!(serde_json::to_value(&result).unwrap(), payload[\"result\"]);")?;
+    write!(test, "  }}")?;
+    tests.push(gate(&test, &method_features(method, api)));
+  }
+
+  let mut result: String = String::new();
+  writeln!(result, "#[cfg(test)]")?;
+  writeln!(result, "mod tests {{")?;
+  writeln!(result, "  use super::*;\n  use super::methods::*;\n\n")?;
+  writeln!(result, "  fn round_trip<T: Serialize + DeserializeOwned + PartialEq + std::fmt::Debug>(value: T) {{")?;
+  writeln!(result, "    let json: {UNKNOWN_TYPE} = serde_json::to_value(&value).unwrap();")?;
+  writeln!(result, "    let parsed: T = serde_json::from_value(json).unwrap();")?;
+  writeln!(result, "    assert_eq!(parsed, value);")?;
+  writeln!(result, "  }}\n\n")?;
+  writeln!(result, "{}", tests.join("\n\n\n"))?;
+  result.push('}');
+
+  Ok(result)
+}
+
+
+/// Emits a bench comparing the key-dispatching `Update` deserializer with a naive flattened one on `UPDATE_PAYLOADS`.
+/// It is a `harness = false` target of the check crate, so it needs the generated api but no bench framework.
+pub(crate) fn generate_bench(types: &HashSet<Type>) -> Result<Option<String>> {
+  let types: BTreeMap<&str, &Type> = deduplicate_types(types);
+  let unions: BTreeMap<String, Union> = collect_unions(&types, &BTreeMap::new());
+  let api: Api = Api {
+    types,
+    unions,
+  };
+  let update: &Type = match api.types.get(UPDATE_TYPE) {
+    Some(update) => update,
+    None => return Ok(None),
+  };
+
+  let mut result: String = String::from("//! Deserialization throughput of `Update` on payloads as the Bot API delivers them.\n\n\n");
+  writeln!(result, "use std::hint::black_box;\nuse std::time::{{Duration, Instant}};\n")?;
+  writeln!(result, "use serde::Deserialize;\n")?;
+  writeln!(result, "use rtg_bot_api_check::api::types::{{{UPDATE_TYPE}, UpdateKind}};\n\n")?;
+  writeln!(result, "const ITERATIONS: u32 = 100_000;\n\n")?;
+
+  writeln!(result, "/// The untagged representation the generated deserializer replaces, which buffers every key before trying the kinds.")?;
+  writeln!(result, "#[derive(Deserialize)]")?;
+  writeln!(result, "#[allow(dead_code)]")?;
+  writeln!(result, "struct NaiveUpdate {{")?;
+  for field in update.fields.iter().filter(|field: &&Field| !field.optional) {
+    writeln!(result, "  {}: {},", field_name(&field.name), rust_type(&field.r#type, &api))?;
+  }
+  writeln!(result, "  #[serde(flatten)]")?;
+  writeln!(result, "  kind: UpdateKind,")?;
+  writeln!(result, "}}\n\n")?;
+
+  writeln!(result, "fn bench<T: for<'de> Deserialize<'de>>(name: &str, payload: &str) {{")?;
+  writeln!(result, "  for _ in 0..ITERATIONS / 10 {{")?;
+  writeln!(result, "    black_box(serde_json::from_str::<T>(black_box(payload)).unwrap());")?;
+  writeln!(result, "  }}")?;
+  writeln!(result, "  let start: Instant = Instant::now();")?;
+  writeln!(result, "  for _ in 0..ITERATIONS {{")?;
+  writeln!(result, "    black_box(serde_json::from_str::<T>(black_box(payload)).unwrap());")?;
+  writeln!(result, "  }}")?;
+  writeln!(result, "  let elapsed: Duration = start.elapsed();")?;
+  writeln!(result, "  let bytes: f64 = payload.len() as f64 * f64::from(ITERATIONS);")?;
+  writeln!(result, "  println!(")?;
+  writeln!(result, "    \"{{name:<32}} {{:>8.0}} ns/update {{:>10.0}} updates/s {{:>8.1}} MiB/s\",")?;
+  writeln!(result, "    elapsed.as_nanos() as f64 / f64::from(ITERATIONS),")?;
+  writeln!(result, "    f64::from(ITERATIONS) / elapsed.as_secs_f64(),")?;
+  writeln!(result, "    bytes / elapsed.as_secs_f64() / 1024.0 / 1024.0,")?;
+  writeln!(result, "  );")?;
+  writeln!(result, "}}\n\n")?;
+
+  writeln!(result, "fn main() {{")?;
+  for (kind, payload) in UPDATE_PAYLOADS {
+    if !update.fields.iter().any(|field: &Field| field.name == kind) {
+      continue;
+    }
+    writeln!(result, "  let payload: &str = r#\"{}\"#;", payload.trim())?;
+    writeln!(result, "  bench::<{UPDATE_TYPE}>(\"{kind} (dispatching)\", payload);")?;
+    writeln!(result, "  bench::<NaiveUpdate>(\"{kind} (naive)\", payload);")?;
+  }
+  result.push('}');
+
+  Ok(Some(result))
+}
+
+
+fn round_trip_test(name: &str, value: &str) -> String {
+  format!("  #[test]\n  fn {}_round_trip() {{\n    round_trip({value});\n  }}", snake_case(name))
+}
+
+
+fn sample(r#type: &str, api: &Api, features: &mut BTreeSet<&'static str>, visiting: &mut Vec<String>) -> Option<String> {
+  if let Some(inner) = r#type.strip_prefix("Vec<").and_then(|inner: &str| inner.strip_suffix('>')) {
+    return sample(inner, api, features, visiting).map(|value: String| format!("vec![{value}]"));
+  }
+
+  match r#type {
+    "i64" => return Some(String::from("1")),
+    "bool" => return Some(String::from("true")),
+    "f64" => return Some(String::from("1.5")),
+    "String" => return Some(String::from("String::from(\"text\")")),
+    UNKNOWN_TYPE => return Some(format!("{UNKNOWN_TYPE}::Null")),
+    _ => (),
+  }
+
+  if let Some(string_enum) = STRING_ENUMS.iter().find(|string_enum: &&StringEnum| string_enum.name == r#type) {
+    return Some(format!("{}::{}", string_enum.name, string_enum.variants[0].0));
+  }
+
+  if let Some(union) = api.unions.values().find(|union: &&Union| union.name == r#type) {
+    return sample(&union.variants[0], api, features, visiting).map(|value: String| format!("{}::from({value})", union.name));
+  }
+
+  let r#type: &Type = api.types.get(r#type)?;
+  if r#type.name == UPDATE_TYPE || visiting.contains(&r#type.name) {
+    return None;
+  }
+
+  features.extend(section_feature(&r#type.section));
+  visiting.push(r#type.name.clone());
+
+  let variants: Vec<&str> = union_variants(r#type);
+  let result: Option<String> = if !variants.is_empty() {
+    variants.iter().find_map(|variant: &&str| {
+      let value: String = sample(variant, api, features, visiting)?;
+      if boxed_type(variant, &r#type.name, api) == *variant {
+        Some(format!("{}::{variant}({value})", r#type.name))
+      } else {
+        Some(format!("{}::{variant}(Box::new({value}))", r#type.name))
+      }
+    })
+  } else {
+    r#type.fields.iter()
+      .filter(|field: &&Field| !field.optional && (field.name != field.r#type || !field.description.is_empty()))
+      .filter(|field: &&Field| fixed_value(field).is_none())
+      .map(|field: &Field| sample(&member_type(&r#type.name, &field.name, &field.r#type, api), api, features, visiting))
+      .collect::<Option<Vec<String>>>()
+      .map(|arguments: Vec<String>| format!("{}::new({})", r#type.name, arguments.join(", ")))
+  };
+
+  visiting.pop();
+  result
+}
+
+
+fn doc_comment(description: &str, anchor: &str) -> String {
+  let link: String = format!("/// <{DOCS_URL}#{anchor}>\n");
+  let description: String = normalize_description(description);
+  if description.is_empty() {
+    return link;
+  }
+
+  format!("/// {description}\n///\n{link}")
+}
+
+
+/// Turns the "Deprecated" marker of a description into a `#[deprecated]` attribute, together with a following "Use X instead." sentence.
+/// The parser adds the marker to struck-through items, and the docs write it for the others.
+fn deprecated(description: &str) -> Option<String> {
+  let description: String = normalize_description(description);
+  let sentences: Vec<&str> = description.split_inclusive(". ").map(str::trim).collect();
+
+  let position: usize = sentences.iter().position(|sentence: &&str| {
+    sentence.strip_prefix("Deprecated").is_some_and(|rest: &str| !rest.starts_with(char::is_alphanumeric))
+  })?;
+
+  let mut note: String = sentences[position].to_string();
+  let next: Option<&&str> = sentences.get(position + 1);
+  if let Some(next) = next.filter(|next: &&&str| next.starts_with("Use ") && next.trim_end_matches('.').ends_with(" instead")) {
+    note = format!("{note} {next}");
+  }
+
+  Some(format!("#[deprecated(note = {note:?})]"))
+}
+
+
+fn normalize_description(description: &str) -> String {
+  description.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+
+fn section_feature(section: &str) -> Option<&'static str> {
+  SECTION_FEATURES.iter()
+    .find(|(name, _): &&(&str, &str)| *name == section)
+    .map(|(_, feature): &(&str, &'static str)| *feature)
+}
+
+
+fn type_feature(r#type: &str, api: &Api) -> Option<&'static str> {
+  api.types.get(strip_vec(r#type)).and_then(|r#type: &&Type| section_feature(&r#type.section))
+}
+
+
+fn member_cfg(owner_section: &str, r#type: &str, api: &Api) -> Option<String> {
+  match type_feature(r#type, api) {
+    Some(feature) if section_feature(owner_section) != Some(feature) => Some(cfg(&[feature])),
+    _ => None,
+  }
+}
+
+
+fn cfg(features: &[&str]) -> String {
+  let features: Vec<String> = features.iter().map(|feature: &&str| format!("feature = \"{feature}\"")).collect();
+  if features.len() == 1 {
+    format!("#[cfg({})]", features[0])
+  } else {
+    format!("#[cfg(all({}))]", features.join(", "))
+  }
+}
+
+
+fn gate(items: &str, features: &[&str]) -> String {
+  if features.is_empty() {
+    return items.to_string();
+  }
+
+  let cfg: String = cfg(features);
+  items.split("\n\n\n")
+    .map(|item: &str| {
+      let indent: &str = &item[..item.len() - item.trim_start().len()];
+      format!("{indent}{cfg}\n{item}")
+    })
+    .collect::<Vec<String>>()
+    .join("\n\n\n")
+}
+
+
+fn member_type(owner: &str, name: &str, r#type: &str, api: &Api) -> String {
+  if r#type != "String" {
+    return rust_type(r#type, api);
+  }
+
+  let string_enum: Option<&StringEnum> = STRING_ENUMS.iter().find(|string_enum: &&StringEnum| {
+    let is_owner: bool = string_enum.owners.is_empty() || string_enum.owners.contains(&owner);
+    let is_field: bool = name == string_enum.field || name.ends_with(&format!("_{}", string_enum.field));
+    is_owner && is_field
+  });
+
+  match string_enum {
+    Some(string_enum) => string_enum.name.to_string(),
+    None => rust_type(r#type, api),
+  }
+}
+
+
+fn rust_type(r#type: &str, api: &Api) -> String {
+  if let Some(inner) = r#type.strip_prefix("Vec<").and_then(|inner: &str| inner.strip_suffix('>')) {
+    return format!("Vec<{}>", rust_type(inner, api));
+  }
+
+  if PRIMITIVE_TYPES.contains(&r#type) || api.types.contains_key(r#type) {
+    return r#type.to_string();
+  }
+
+  if let Some(union) = api.unions.get(r#type) {
+    return union.name.clone();
+  }
+
+  if let Some(r#type) = true_result(r#type, api) {
+    return format!("{type}OrTrue");
+  }
+
+  String::from(UNKNOWN_TYPE)
+}
+
+
+fn boxed_type(r#type: &str, owner: &str, api: &Api) -> String {
+  if references(r#type, owner, &api.types, &mut HashSet::new()) {
+    return format!("Box<{type}>");
+  }
+
+  r#type.to_string()
+}
+
+
+fn references<'a>(r#type: &'a str, target: &str, types: &BTreeMap<&str, &'a Type>, visited: &mut HashSet<&'a str>) -> bool {
+  if r#type == target {
+    return true;
+  }
+
+  let r#type: &Type = match types.get(r#type) {
+    Some(r#type) => r#type,
+    None => return false,
+  };
+
+  if !visited.insert(r#type.name.as_str()) {
+    return false;
+  }
+
+  let variants: Vec<&str> = union_variants(r#type);
+  if !variants.is_empty() {
+    return variants.into_iter().any(|variant: &str| references(variant, target, types, visited));
+  }
+
+  r#type.fields.iter().any(|field: &Field| references(&field.r#type, target, types, visited))
+}
+
+
+fn field_name(name: &str) -> String {
+  if KEYWORDS.contains(&name) {
+    return format!("r#{name}");
+  }
+
+  name.to_string()
+}
+
+
+fn snake_case(name: &str) -> String {
+  let mut result: String = String::new();
+
+  for (i, ch) in name.chars().enumerate() {
+    if ch.is_uppercase() && i != 0 {
+      result.push('_');
+    }
+    result.extend(ch.to_lowercase());
+  }
+
+  result
+}
+
+
+fn pascal_case(name: &str) -> String {
+  name.split('_')
+    .map(|part: &str| {
+      let mut chars: std::str::Chars = part.chars();
+      match chars.next() {
+        Some(ch) => ch.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+      }
+    })
+    .collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+
+  fn field(name: &str, r#type: &str, description: &str) -> Field {
+    Field::new(name.to_string(), r#type.to_string(), false, description.to_string())
+  }
+
+
+  fn object(name: &str, fields: Vec<Field>) -> Type {
+    Type::new(name.to_string(), String::new(), String::from("Available types"), name.to_lowercase(), fields)
+  }
+
+
+  fn api(types: &[Type]) -> Api<'_> {
+    let types: BTreeMap<&str, &Type> = types.iter().map(|r#type: &Type| (r#type.name.as_str(), r#type)).collect();
+    let unions: BTreeMap<String, Union> = collect_unions(&types, &BTreeMap::new());
+    Api {
+      types,
+      unions,
+    }
+  }
+
+
+  fn message_types() -> Vec<Type> {
+    vec![
+      object("Message", vec![field("message_id", "i64", ""), field("date", "i64", "")]),
+      object("InaccessibleMessage", vec![field("message_id", "i64", ""), field("date", "i64", "Always 0. The field can be used to differentiate regular and inaccessible messages.")]),
+      object("InlineKeyboardMarkup", vec![field("inline_keyboard", "Vec<Vec<String>>", "")]),
+      object("ForceReply", vec![field("force_reply", "bool", "")]),
+      object("CallbackQuery", vec![
+        field("message", "Message or InaccessibleMessage", ""),
+        field("reply_markup", "InlineKeyboardMarkup or ForceReply", ""),
+      ]),
+    ]
+  }
+
+
+  #[test]
+  fn type_mapping() {
+    let types: Vec<Type> = message_types();
+    let api: Api = api(&types);
+
+    assert_eq!(rust_type("i64", &api), "i64");
+    assert_eq!(rust_type("Vec<Vec<Message>>", &api), "Vec<Vec<Message>>");
+    assert_eq!(rust_type("InlineKeyboardMarkup or ForceReply", &api), "ReplyMarkup");
+    assert_eq!(rust_type("Message or True", &api), "MessageOrTrue");
+    assert_eq!(rust_type("Unheard", &api), UNKNOWN_TYPE);
+    assert_eq!(member_type("sendMessage", "parse_mode", "String", &api), "ParseMode");
+    assert_eq!(member_type("Message", "caption_parse_mode", "String", &api), "ParseMode");
+    assert_eq!(member_type("sendDice", "emoji", "String", &api), "DiceEmoji");
+    assert_eq!(member_type("Sticker", "emoji", "String", &api), "String");
+  }
+
+
+  #[test]
+  fn union_naming() {
+    let types: Vec<Type> = message_types();
+    let api: Api = api(&types);
+
+    let names: Vec<(&str, &str)> = api.unions.iter().map(|(r#type, union): (&String, &Union)| (r#type.as_str(), union.name.as_str())).collect();
+    assert_eq!(names, [
+      ("InlineKeyboardMarkup or ForceReply", "ReplyMarkup"),
+      ("Message or InaccessibleMessage", "MessageOrInaccessibleMessage"),
+    ]);
+    assert_eq!(api.unions["Message or InaccessibleMessage"].variants, ["Message", "InaccessibleMessage"]);
+    assert_eq!(union_type_variants("Message or Unheard", &api.types), None);
+    assert_eq!(union_type_variants("InlineKeyboardMarkup, ForceReply and Message", &api.types).unwrap().len(), 3);
+  }
+
+
+  #[test]
+  fn deprecated_marker() {
+    assert_eq!(deprecated("Deprecated. Use refundStarPayment instead. Use this method to get an invoice."), Some(String::from("#[deprecated(note = \"Deprecated. Use refundStarPayment instead.\")]")));
+    assert_eq!(deprecated("Optional. Previous username of the user. Deprecated."), Some(String::from("#[deprecated(note = \"Deprecated.\")]")));
+    assert_eq!(deprecated("If the message is a reply, ID of the original message. Deprecated, use reply_parameters instead."), Some(String::from("#[deprecated(note = \"Deprecated, use reply_parameters instead.\")]")));
+    assert_eq!(deprecated("Unique identifier for the target chat"), None);
+  }
+
+
+  #[test]
+  fn deprecated_guidance_is_not_a_marker() {
+    assert_eq!(deprecated("Use this method to send answers to callback queries instead of messages."), None);
+    assert_eq!(deprecated("Use getFile to download the file instead."), None);
+    assert_eq!(deprecated("Pass True to drop the deprecated fields. The old values are kept."), None);
+    assert_eq!(deprecated("Undeprecated fields stay as they are."), None);
+    assert_eq!(deprecated("Deprecatedness is unknown."), None);
+  }
+
+
+  #[test]
+  fn default_extraction() {
+    assert_eq!(default_value("i64", "Values between 1-100 are accepted. Defaults to 100."), Some(("i64", String::from("100"))));
+    assert_eq!(default_value("i64", "Timeout in seconds. DEFAULTS TO 0, i.e. usual short polling."), Some(("i64", String::from("0"))));
+    assert_eq!(default_value("f64", "Made-up ratio. Defaults to 0.5."), Some(("f64", String::from("0.5"))));
+    assert_eq!(default_value("bool", "True, if anonymous, defaults to True"), Some(("bool", String::from("true"))));
+    assert_eq!(default_value("String", "Made-up kind, “quiz” or “regular”, defaults to “regular”"), Some(("&str", String::from("\"regular\""))));
+    assert_eq!(default_value("String", "Defaults to the value of something"), None);
+    assert_eq!(default_value("i64", "Defaults to the chat's slow mode delay"), None);
+    assert_eq!(default_value("i64", "Identifier of the first update to be returned"), None);
+  }
+
+
+  #[test]
+  fn defaults_in_constructor() {
+    let member = |name: &str, r#type: &str, description: &str| -> Member {
+      Member { name: name.to_string(), r#type: r#type.to_string(), required: false, cfg: None, description: description.to_string(), value: None }
+    };
+    let members: [Member; 3] = [
+      member("offset", "i64", "Identifier of the first update to be returned."),
+      member("limit", "i64", "Values between 1-100 are accepted. Defaults to 100."),
+      member("kind", "String", "Made-up kind, “quiz” or “regular”, defaults to “regular”"),
+    ];
+    let result: String = generate_struct("GetUpdates", &members, false).unwrap();
+
+    assert!(result.contains("pub const DEFAULT_LIMIT: i64 = 100;"));
+    assert!(result.contains("offset: None,"));
+    assert!(result.contains("limit: Some(Self::DEFAULT_LIMIT),"));
+    assert!(result.contains("kind: Some(String::from(Self::DEFAULT_KIND)),"));
+    assert!(result.contains("impl Default for GetUpdates {\n  fn default() -> Self {\n    Self::new()\n  }\n}"));
+    assert!(!result.contains("derive(Debug, Clone, Default"));
+  }
+
+
+  #[test]
+  fn union_deserializer_order() {
+    let types: Vec<Type> = message_types();
+    let variants: [&str; 2] = ["Message", "InaccessibleMessage"];
+    let result: String = generate_union_deserializer("MaybeInaccessibleMessage", "Available types", &variants, &api(&types)).unwrap();
+
+    let tagged: usize = result.find("value.get(\"date\").is_some_and(|field: &serde_json::Value| *field == 0)").unwrap();
+    let inaccessible: usize = result.find("return Ok(Self::InaccessibleMessage(variant));").unwrap();
+    let message: usize = result.find("return Ok(Self::Message(variant));").unwrap();
+    let unknown: usize = result.find("Ok(Self::Unknown(value))").unwrap();
+    assert!(tagged < inaccessible && inaccessible < message && message < unknown);
+  }
+
+
+  #[test]
+  fn union_variants_sharing_a_tag() {
+    let types: [Type; 2] = [
+      object("InlineQueryResultCachedPhoto", vec![field("type", "String", "Type of the result, must be photo"), field("photo_file_id", "String", "")]),
+      object("InlineQueryResultPhoto", vec![field("type", "String", "Type of the result, must be photo"), field("photo_url", "String", "")]),
+    ];
+    let variants: [&str; 2] = ["InlineQueryResultCachedPhoto", "InlineQueryResultPhoto"];
+    let result: String = generate_union_deserializer("InlineQueryResult", "Available types", &variants, &api(&types)).unwrap();
+
+    let cached: usize = result.find("return Ok(Self::InlineQueryResultCachedPhoto(variant));").unwrap();
+    let photo: usize = result.find("return Ok(Self::InlineQueryResultPhoto(variant));").unwrap();
+    let unknown: usize = result.find("Ok(Self::Unknown(value))").unwrap();
+    assert!(cached < photo && photo < unknown);
+    assert_eq!(result.matches("*field == \"photo\"").count(), 2);
+    assert!(!result.contains("map_err"));
+  }
+
+
+  #[test]
+  fn update_bench() {
+    let types: HashSet<Type> = HashSet::from([object("Update", vec![
+      field("update_id", "i64", ""),
+      Field::new(String::from("message"), String::from("Message"), true, String::new()),
+      Field::new(String::from("callback_query"), String::from("CallbackQuery"), true, String::new()),
+    ])]);
+    let result: String = generate_bench(&types).unwrap().unwrap();
+
+    assert!(result.contains("  update_id: i64,\n  #[serde(flatten)]\n  kind: UpdateKind,"));
+    assert!(result.contains("bench::<Update>(\"message (dispatching)\", payload);"));
+    assert!(result.contains("bench::<NaiveUpdate>(\"callback_query (naive)\", payload);"));
+    assert!(!result.contains("inline_query"));
+    assert_eq!(generate_bench(&HashSet::new()).unwrap(), None);
+  }
+}
@@ -0,0 +1,143 @@
+//!   Rust telegram bot api. The library provides asynchronous access to the telegram bot api.
+//!   Copyright (C) 2024  Andrew Kozmin
+//!
+//!   This program is free software: you can redistribute it and/or modify
+//!   it under the terms of the GNU Affero General Public License as published by
+//!   the Free Software Foundation, either version 3 of the License, or
+//!   (at your option) any later version.
+//!
+//!   This program is distributed in the hope that it will be useful,
+//!   but WITHOUT ANY WARRANTY; without even the implied warranty of
+//!   MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//!   GNU Affero General Public License for more details.
+//!
+//!   You should have received a copy of the GNU Affero General Public License
+//!   along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+
+use anyhow::Result;
+use serde::Serialize;
+
+
+#[derive(Default)]
+struct Bindings {
+  items: BTreeMap<String, BTreeSet<String>>,
+  methods: BTreeSet<String>,
+}
+
+
+#[derive(Serialize)]
+struct Entry {
+  kind: &'static str,
+  name: String,
+  status: &'static str,
+}
+
+
+/// Compares the bindings in `current` with the ones generated from a fresh parse and prints what is missing or stale.
+pub(crate) fn report(current: &str, fresh: &str, json_path: Option<&str>) -> Result<()> {
+  let current: Bindings = scan(current);
+  let fresh: Bindings = scan(fresh);
+  let mut entries: Vec<Entry> = Vec::new();
+
+  compare("method", &current.methods, &fresh.methods, &mut entries);
+
+  let current_items: BTreeSet<String> = current.items.keys().cloned().collect();
+  let fresh_items: BTreeSet<String> = fresh.items.keys().cloned().collect();
+  compare("item", &current_items, &fresh_items, &mut entries);
+
+  for (name, fresh_members) in &fresh.items {
+    if let Some(current_members) = current.items.get(name) {
+      let qualify = |members: &BTreeSet<String>| -> BTreeSet<String> {
+        members.iter().map(|member: &String| format!("{name}.{member}")).collect()
+      };
+      compare("member", &qualify(current_members), &qualify(fresh_members), &mut entries);
+    }
+  }
+
+  if let Some(json_path) = json_path {
+    fs::write(json_path, serde_json::to_string_pretty(&entries)?)?;
+  }
+
+  if entries.is_empty() {
+    println!("The bindings are up to date");
+    return Ok(());
+  }
+
+  let width: usize = entries.iter().map(|entry: &Entry| entry.name.len()).max().unwrap_or_default().max(4);
+  println!("{:<8} {:<width$} STATUS", "KIND", "NAME");
+  for entry in &entries {
+    println!("{:<8} {:<width$} {}", entry.kind, entry.name, entry.status);
+  }
+
+  Ok(())
+}
+
+
+fn compare(kind: &'static str, current: &BTreeSet<String>, fresh: &BTreeSet<String>, entries: &mut Vec<Entry>) {
+  for name in fresh.difference(current) {
+    entries.push(Entry {
+      kind,
+      name: name.clone(),
+      status: "missing",
+    });
+  }
+
+  for name in current.difference(fresh) {
+    entries.push(Entry {
+      kind,
+      name: name.clone(),
+      status: "stale",
+    });
+  }
+}
+
+
+fn scan(source: &str) -> Bindings {
+  let mut result: Bindings = Bindings::default();
+  let mut item: Option<String> = None;
+
+  for line in source.lines() {
+    if let Some(name) = line.trim().strip_prefix("const METHOD: &'static str = \"") {
+      result.methods.insert(name.trim_end_matches("\";").to_string());
+      continue;
+    }
+
+    let declaration: Option<&str> = line.strip_prefix("pub struct ").or_else(|| line.strip_prefix("pub enum "));
+    if let Some(declaration) = declaration {
+      let name: String = declaration.chars().take_while(|ch: &char| ch.is_alphanumeric() || *ch == '_').collect();
+      result.items.entry(name.clone()).or_default();
+      if declaration.ends_with('{') {
+        item = Some(name);
+      }
+      continue;
+    }
+
+    if line.starts_with('}') {
+      item = None;
+      continue;
+    }
+
+    let name: &String = match &item {
+      Some(name) => name,
+      None => continue,
+    };
+
+    let member: &str = match line.strip_prefix("  ") {
+      Some(member) if !member.starts_with([' ', '#', '/']) => member,
+      _ => continue,
+    };
+
+    let member: &str = member.strip_prefix("pub ").unwrap_or(member);
+    let member: &str = member.strip_prefix("r#").unwrap_or(member);
+    let member: String = member.chars().take_while(|ch: &char| ch.is_alphanumeric() || *ch == '_').collect();
+    if !member.is_empty() && member != "extra" {
+      result.items.entry(name.clone()).or_default().insert(member);
+    }
+  }
+
+  result
+}
@@ -0,0 +1,163 @@
+//!   Rust telegram bot api. The library provides asynchronous access to the telegram bot api.
+//!   Copyright (C) 2024  Andrew Kozmin
+//!
+//!   This program is free software: you can redistribute it and/or modify
+//!   it under the terms of the GNU Affero General Public License as published by
+//!   the Free Software Foundation, either version 3 of the License, or
+//!   (at your option) any later version.
+//!
+//!   This program is distributed in the hope that it will be useful,
+//!   but WITHOUT ANY WARRANTY; without even the implied warranty of
+//!   MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//!   GNU Affero General Public License for more details.
+//!
+//!   You should have received a copy of the GNU Affero General Public License
+//!   along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+
+use std::collections::HashSet;
+
+use serde_json::{json, Map, Value};
+
+use crate::codegen;
+use crate::tg_api::{Type, Method, Field};
+
+
+const SCHEMA_REF: &str = "#/components/schemas/";
+
+
+/// Describes the parsed API as an OpenAPI document, with a JSON Schema for every type under `components.schemas`.
+pub(crate) fn openapi(types: &HashSet<Type>, methods: &HashSet<Method>, version: &str) -> Value {
+  let type_names: HashSet<&str> = types.iter().map(|r#type: &Type| r#type.name.as_str()).collect();
+
+  let mut schemas: Map<String, Value> = Map::new();
+  for (name, r#type) in codegen::deduplicate_types(types) {
+    schemas.insert(name.to_string(), type_schema(r#type, &type_names));
+  }
+
+  let mut paths: Map<String, Value> = Map::new();
+  for method in methods {
+    paths.insert(format!("/{}", method.name), method_schema(method, &type_names));
+  }
+
+  json!({
+    "openapi": "3.1.0",
+    "info": {
+      "title": "Telegram Bot API",
+      "version": version,
+    },
+    "servers": [{
+      "url": "https://api.telegram.org/bot{token}",
+      "variables": { "token": { "default": "" } },
+    }],
+    "paths": paths,
+    "components": { "schemas": schemas },
+  })
+}
+
+
+fn type_schema(r#type: &Type, type_names: &HashSet<&str>) -> Value {
+  let is_union: bool = !r#type.fields.is_empty() && r#type.fields.iter().all(|field: &Field| field.name == field.r#type);
+  if is_union {
+    let variants: Vec<Value> = r#type.fields.iter().map(|field: &Field| schema(&field.r#type, type_names)).collect();
+    return json!({ "description": r#type.description, "oneOf": variants });
+  }
+
+  let mut properties: Map<String, Value> = Map::new();
+  let mut required: Vec<&str> = Vec::new();
+  for field in &r#type.fields {
+    properties.insert(field.name.clone(), described(schema(&field.r#type, type_names), &field.description));
+    if !field.optional {
+      required.push(&field.name);
+    }
+  }
+
+  json!({
+    "description": r#type.description,
+    "type": "object",
+    "properties": properties,
+    "required": required,
+  })
+}
+
+
+fn method_schema(method: &Method, type_names: &HashSet<&str>) -> Value {
+  let mut properties: Map<String, Value> = Map::new();
+  let mut required: Vec<&str> = Vec::new();
+  for parameter in &method.parameters {
+    properties.insert(parameter.name.clone(), described(schema(&parameter.r#type, type_names), &parameter.description));
+    if parameter.required {
+      required.push(&parameter.name);
+    }
+  }
+
+  let mut operation: Value = json!({
+    "operationId": method.name,
+    "description": method.description,
+    "responses": {
+      "200": {
+        "description": "The request was successful",
+        "content": {
+          "application/json": {
+            "schema": {
+              "type": "object",
+              "properties": {
+                "ok": { "type": "boolean" },
+                "result": schema(&method.return_type, type_names),
+              },
+              "required": ["ok", "result"],
+            },
+          },
+        },
+      },
+    },
+  });
+
+  if !method.parameters.is_empty() {
+    operation["requestBody"] = json!({
+      "required": !required.is_empty(),
+      "content": {
+        "application/json": {
+          "schema": { "type": "object", "properties": properties, "required": required },
+        },
+      },
+    });
+  }
+
+  json!({ "post": operation })
+}
+
+
+fn schema(r#type: &str, type_names: &HashSet<&str>) -> Value {
+  if let Some(element) = r#type.strip_prefix("Vec<").and_then(|element: &str| element.strip_suffix('>')) {
+    return json!({ "type": "array", "items": schema(element, type_names) });
+  }
+
+  let variants: Vec<&str> = r#type.split(" or ")
+    .flat_map(|part: &str| part.split(", "))
+    .flat_map(|part: &str| part.split(" and "))
+    .collect();
+  if variants.len() > 1 {
+    let variants: Vec<Value> = variants.into_iter().map(|variant: &str| schema(variant, type_names)).collect();
+    return json!({ "oneOf": variants });
+  }
+
+  match r#type {
+    "i64" | "Integer" | "Int" => json!({ "type": "integer" }),
+    "f64" | "Float" => json!({ "type": "number" }),
+    "bool" | "Boolean" => json!({ "type": "boolean" }),
+    "True" => json!({ "type": "boolean", "const": true }),
+    "String" => json!({ "type": "string" }),
+    _ if type_names.contains(r#type) => json!({ "$ref": format!("{SCHEMA_REF}{type}") }),
+    _ => json!({}),
+  }
+}
+
+
+fn described(mut schema: Value, description: &str) -> Value {
+  if let Some(schema) = schema.as_object_mut() {
+    schema.insert(String::from("description"), Value::from(description));
+  }
+  schema
+}
+
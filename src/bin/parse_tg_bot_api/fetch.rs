@@ -0,0 +1,208 @@
+//!   Rust telegram bot api. The library provides asynchronous access to the telegram bot api.
+//!   Copyright (C) 2024  Andrew Kozmin
+//!
+//!   This program is free software: you can redistribute it and/or modify
+//!   it under the terms of the GNU Affero General Public License as published by
+//!   the Free Software Foundation, either version 3 of the License, or
+//!   (at your option) any later version.
+//!
+//!   This program is distributed in the hope that it will be useful,
+//!   but WITHOUT ANY WARRANTY; without even the implied warranty of
+//!   MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//!   GNU Affero General Public License for more details.
+//!
+//!   You should have received a copy of the GNU Affero General Public License
+//!   along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+
+use std::fs::{self, DirEntry};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Error, Result};
+use reqwest::{
+  header::{HeaderName, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, RETRY_AFTER},
+  Client, RequestBuilder, Response, StatusCode,
+};
+use rtg_bot_api::backoff::Backoff;
+
+use crate::output;
+
+
+const API_URL: &str = "https://core.telegram.org/bots/api";
+
+const CHANGELOG_URL: &str = "https://core.telegram.org/bots/api-changelog";
+
+const CACHE_DIR: &str = "target/api_cache";
+
+const ARCHIVE_DIR: &str = "api_archive";
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+const MAX_ATTEMPTS: u32 = 5;
+
+
+/// The fetched docs page and whether it differs from the cached copy.
+pub(crate) struct Page {
+  pub(crate) html: String,
+  pub(crate) changed: bool,
+  etag: Option<String>,
+  last_modified: Option<String>,
+}
+
+
+impl Page {
+  fn new(html: String, changed: bool, etag: Option<String>, last_modified: Option<String>) -> Self {
+    Self {
+      html,
+      changed,
+      etag,
+      last_modified,
+    }
+  }
+
+
+  /// Stores the page and its validators, so the next run can send a conditional request.
+  pub(crate) fn save(&self) -> Result<()> {
+    let cache: &Path = Path::new(CACHE_DIR);
+    fs::create_dir_all(cache)?;
+    output::write_if_changed(&cache.join("api.html"), &self.html)?;
+
+    for (name, value) in [("etag", &self.etag), ("last_modified", &self.last_modified)] {
+      match value {
+        Some(value) => output::write_if_changed(&cache.join(name), value)?,
+        None if cache.join(name).exists() => fs::remove_file(cache.join(name))?,
+        None => (),
+      }
+    }
+
+    Ok(())
+  }
+}
+
+
+/// Fetches the docs page, falling back to `mirror` when the official site keeps failing.
+///
+/// The cached `ETag` and `Last-Modified` validators are sent unless `force` is set.
+pub(crate) async fn get_html(force: bool, mirror: Option<&str>) -> Result<Page> {
+  let cache: &Path = Path::new(CACHE_DIR);
+  let cached: Option<String> = fs::read_to_string(cache.join("api.html")).ok();
+
+  let mut validators: Vec<(HeaderName, String)> = Vec::new();
+  if !force && cached.is_some() {
+    if let Ok(etag) = fs::read_to_string(cache.join("etag")) {
+      validators.push((IF_NONE_MATCH, etag));
+    }
+    if let Ok(last_modified) = fs::read_to_string(cache.join("last_modified")) {
+      validators.push((IF_MODIFIED_SINCE, last_modified));
+    }
+  }
+
+  let client: Client = Client::builder().timeout(TIMEOUT).build()?;
+  let mut error: Error = anyhow!("ERROR: No url to fetch the docs from");
+  for url in std::iter::once(API_URL).chain(mirror) {
+    let response: Response = match send(&client, url, &validators).await {
+      Ok(response) => response,
+      Err(e) => {
+        eprintln!("{e}");
+        error = e;
+        continue;
+      },
+    };
+
+    if response.status() == StatusCode::NOT_MODIFIED {
+      let html: String = cached.context("ERROR: The docs were not modified, but there is no cached copy")?;
+      let etag: Option<String> = fs::read_to_string(cache.join("etag")).ok();
+      let last_modified: Option<String> = fs::read_to_string(cache.join("last_modified")).ok();
+      return Ok(Page::new(html, false, etag, last_modified));
+    }
+
+    let etag: Option<String> = response.headers().get(ETAG).and_then(|value: &HeaderValue| value.to_str().ok()).map(String::from);
+    let last_modified: Option<String> = response.headers().get(LAST_MODIFIED).and_then(|value: &HeaderValue| value.to_str().ok()).map(String::from);
+    let html: String = response.text().await?;
+
+    let changed: bool = cached.as_ref() != Some(&html);
+    return Ok(Page::new(html, changed, etag, last_modified));
+  }
+
+  Err(error)
+}
+
+
+/// Reads the docs page archived for `version` instead of fetching the live one.
+pub(crate) fn read_archive(version: &str) -> Result<Page> {
+  let path: PathBuf = Path::new(ARCHIVE_DIR).join(format!("{version}.html"));
+  if !path.exists() {
+    let mut archived: Vec<String> = match fs::read_dir(ARCHIVE_DIR) {
+      Ok(entries) => entries
+        .filter_map(|entry: io::Result<DirEntry>| entry.ok()?.file_name().to_str()?.strip_suffix(".html").map(String::from))
+        .collect(),
+      Err(_) => Vec::new(),
+    };
+    archived.sort();
+    bail!("ERROR: There is no archived docs page for Bot API {version}, the archived versions are: {}", archived.join(", "));
+  }
+
+  let html: String = fs::read_to_string(&path).context(format!("ERROR: Failed to read {}", path.display()))?;
+  Ok(Page::new(html, true, None, None))
+}
+
+
+/// Archives the docs page as `api_archive/<version>.html`, so the bindings for this version can be reproduced later.
+pub(crate) fn archive(html: &str, version: &str) -> Result<()> {
+  fs::create_dir_all(ARCHIVE_DIR)?;
+  output::write_if_changed(&Path::new(ARCHIVE_DIR).join(format!("{version}.html")), html)
+}
+
+
+/// Fetches the changelog page with the full version history, or `None` if it is unavailable.
+pub(crate) async fn get_changelog() -> Option<String> {
+  let client: Client = Client::builder().timeout(TIMEOUT).build().ok()?;
+  let html: Result<String> = match send(&client, CHANGELOG_URL, &[]).await {
+    Ok(response) => response.text().await.map_err(Error::from),
+    Err(e) => Err(e),
+  };
+
+  match html {
+    Ok(html) => Some(html),
+    Err(e) => {
+      eprintln!("{e}");
+      None
+    },
+  }
+}
+
+
+/// Sends the request, retrying network errors, timeouts and server errors with exponential backoff.
+async fn send(client: &Client, url: &str, validators: &[(HeaderName, String)]) -> Result<Response> {
+  let backoff: Backoff = Backoff::default();
+  let mut attempt: u32 = 0;
+
+  loop {
+    let mut request: RequestBuilder = client.get(url);
+    for (name, value) in validators {
+      request = request.header(name, value);
+    }
+
+    let (error, retry_after): (String, Option<Duration>) = match request.send().await {
+      Ok(response) if response.status().is_success() || response.status() == StatusCode::NOT_MODIFIED => return Ok(response),
+      Ok(response) if response.status().is_server_error() || response.status() == StatusCode::TOO_MANY_REQUESTS => {
+        let retry_after: Option<Duration> = response.headers().get(RETRY_AFTER)
+          .and_then(|value: &HeaderValue| value.to_str().ok()?.parse().ok())
+          .map(Duration::from_secs);
+        (format!("Request to {url} failed with {}", response.status()), retry_after)
+      },
+      Ok(response) => bail!("ERROR: Request to {url} failed with {}", response.status()),
+      Err(e) => (format!("Request to {url} failed: {e}"), None),
+    };
+
+    attempt += 1;
+    if attempt == MAX_ATTEMPTS {
+      bail!("ERROR: {error} after {MAX_ATTEMPTS} attempts");
+    }
+
+    eprintln!("WARNING: {error}, retrying");
+    tokio::time::sleep(backoff.retry_delay(attempt - 1, retry_after)).await;
+  }
+}
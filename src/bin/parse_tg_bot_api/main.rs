@@ -17,60 +17,171 @@
 
 mod tg_api;
 mod parser;
+mod codegen;
+mod check;
+mod coverage;
+mod export;
+mod fetch;
+mod output;
+mod rustfmt;
+mod snapshot;
+
+
+use std::collections::{BTreeMap, HashSet};
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use select::document::Document;
 
+use crate::tg_api::{Type, Method, Version};
+use crate::parser::Tag;
+use crate::fetch::Page;
+use crate::snapshot::Snapshot;
 
-use std::collections::HashSet;
 
-use anyhow::{Result, bail};
-use reqwest::Response;
-use select::document::Document;
+const API_DIR: &str = "src/api";
+
+const SNAPSHOT_PATH: &str = "api.json";
+
+const SNAPSHOTS_DIR: &str = "api_snapshots";
 
-use crate::tg_api::{Type, Method};
-use crate::parser::{Tag, LineTag};
+const VERSIONS_PATH: &str = "api_versions.json";
+
+const CHANGES_PATH: &str = "CHANGES_API.md";
+
+const OPENAPI_PATH: &str = "openapi.json";
 
 
 #[tokio::main]
 async fn main() {
   match main_wraper().await {
     Ok(_) => println!("PARSE SUNCCESS!"),
-    Err(e) => eprintln!("{e}"),
+    Err(e) => {
+      eprintln!("{e}");
+      std::process::exit(1);
+    },
   }
 }
 
 
 async fn main_wraper() -> Result<()> {
-  let html: String = get_html().await?;
-  let document: Document = Document::from(html.as_str());
-  let tags: Vec<Tag> = parser::get_list_of_main_tags(&document)?;
+  let command: Option<String> = env::args().nth(1).filter(|arg: &String| !arg.starts_with('-'));
+  let mirror: Option<String> = env::args().skip_while(|arg: &String| arg != "--mirror").nth(1);
+  let api_version: Option<String> = env::args().skip_while(|arg: &String| arg != "--api-version").nth(1);
+  let force: bool = env::args().any(|arg: String| arg == "--force");
+  let page: Page = match &api_version {
+    Some(api_version) => fetch::read_archive(api_version)?,
+    None => fetch::get_html(force, mirror.as_deref()).await?,
+  };
+  // Pinned versions get their own bindings and snapshot, so they never overwrite the live ones.
+  let (api_dir, snapshot_path): (String, String) = match &api_version {
+    Some(api_version) => (format!("{API_DIR}_v{}", api_version.replace('.', "_")), format!("{SNAPSHOTS_DIR}/{api_version}.json")),
+    None => (String::from(API_DIR), String::from(SNAPSHOT_PATH)),
+  };
+  if !page.changed && !force && command.is_none() && Path::new(&snapshot_path).exists() {
+    println!("API UNCHANGED");
+    return Ok(());
+  }
+
+  let document: Document = Document::from(page.html.as_str());
+  let mut anomalies: Vec<String> = Vec::new();
+  let tags: Vec<Tag> = parser::get_list_of_main_tags(&document, &mut anomalies)?;
   let (types, methods): (HashSet<Type>, HashSet<Method>) = parser::parse_api(&tags)?;
+  anomalies.extend(parser::validate_types(&types, &methods));
+  report_anomalies(&anomalies, env::args().any(|arg: String| arg == "--strict"))?;
+
+  let versions: Vec<Version> = parser::parse_versions(&tags);
+  if command.as_deref() == Some("export") {
+    let path: String = env::args().nth(2).filter(|arg: &String| !arg.starts_with('-')).unwrap_or(String::from(OPENAPI_PATH));
+    let version: &str = versions.first().map_or("", |version: &Version| version.name.as_str());
+    return output::write_if_changed(Path::new(&path), &serde_json::to_string_pretty(&export::openapi(&types, &methods, version))?);
+  }
 
-  for i in tags {
-    match i {
-      Tag::H4Tag(tag) => println!("{:?}", tag.value),
-      Tag::PTag(tag) => println!("{:?}", tag.value),
-      Tag::TableTag(tag) => tag.lines.iter().for_each(|line: &LineTag| println!("{:?}", line.value)),
-      Tag::UlTag(tag) => println!("{:?}", tag.list_items),
+  let files: BTreeMap<String, String> = codegen::generate(&types, &methods, &versions)?;
+  if command.as_deref() == Some("coverage") {
+    let current: String = output::read_files(&api_dir)?;
+    if current.is_empty() {
+      bail!("ERROR: There are no generated bindings to compare with");
     }
+    let json: Option<String> = env::args().skip_while(|arg: &String| arg != "--json").nth(1);
+    return coverage::report(&current, &files.into_values().collect::<String>(), json.as_deref());
   }
 
-  for i in types {
-    for j in i.fields {
-      println!("{:?}", j)
+  let snapshot: Snapshot = Snapshot::new(&types, &methods);
+  let previous: Option<Snapshot> = Snapshot::load(&snapshot_path)?;
+  if let Some(previous) = &previous {
+    for change in snapshot.changes(previous) {
+      println!("{change}");
     }
   }
+  let report: Option<String> = previous.and_then(|previous: Snapshot| snapshot.report(&previous));
+
+  let files: BTreeMap<String, String> = files.into_iter()
+    .map(|(path, source): (String, String)| Ok((path, rustfmt::format(source)?)))
+    .collect::<Result<BTreeMap<String, String>>>()?;
+  if !env::args().any(|arg: String| arg == "--skip-check") {
+    let bench: Option<String> = codegen::generate_bench(&types)?.map(rustfmt::format).transpose()?;
+    check::check(&files, bench.as_deref(), env::args().any(|arg: String| arg == "--bench"))?;
+  }
+  output::write_files(&api_dir, &files)?;
+  if api_version.is_some() {
+    fs::create_dir_all(SNAPSHOTS_DIR).context(format!("ERROR: Failed to create {SNAPSHOTS_DIR}"))?;
+  }
+  snapshot.save(&snapshot_path)?;
+  if api_version.is_none() {
+    if let Some(report) = report {
+      output::write_if_changed(Path::new(CHANGES_PATH), &report)?;
+    }
+    if let Some(version) = versions.first() {
+      fetch::archive(&page.html, &version.name)?;
+    }
+    let versions: Vec<Version> = collect_versions(versions).await;
+    output::write_if_changed(Path::new(VERSIONS_PATH), &serde_json::to_string_pretty(&versions)?)?;
+    page.save()?;
+  }
 
   Ok(())
 }
 
 
-async fn get_html() -> Result<String> {
-  let url: String = String::from("https://core.telegram.org/bots/api");
-  let response: Response = reqwest::get(&url).await?;
-  
-  if !response.status().is_success() {
-    bail!("ERROR: Request to {} failed with {}", url, response.status());
+/// Appends the older versions from the changelog page to the ones listed under "Recent changes".
+async fn collect_versions(mut versions: Vec<Version>) -> Vec<Version> {
+  let html: String = match fetch::get_changelog().await {
+    Some(html) => html,
+    None => return versions,
+  };
+
+  let document: Document = Document::from(html.as_str());
+  let tags: Vec<Tag> = match parser::get_list_of_main_tags(&document, &mut Vec::new()) {
+    Ok(tags) => tags,
+    Err(e) => {
+      eprintln!("{e}");
+      return versions;
+    },
+  };
+
+  for version in parser::parse_versions(&tags) {
+    if !versions.iter().any(|known: &Version| known.name == version.name) {
+      versions.push(version);
+    }
   }
 
-  let html: String = response.text().await?;
-  Ok(html)
+  versions
 }
+
+
+fn report_anomalies(anomalies: &[String], strict: bool) -> Result<()> {
+  if strict && !anomalies.is_empty() {
+    let report: String = anomalies.iter().map(|anomaly: &String| format!("\n  {anomaly}")).collect();
+    bail!("ERROR: Found {} anomalies in the documentation:{report}", anomalies.len());
+  }
+
+  for anomaly in anomalies {
+    eprintln!("WARNING: {anomaly}");
+  }
+
+  Ok(())
+}
+
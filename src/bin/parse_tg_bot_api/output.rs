@@ -0,0 +1,89 @@
+//!   Rust telegram bot api. The library provides asynchronous access to the telegram bot api.
+//!   Copyright (C) 2024  Andrew Kozmin
+//!
+//!   This program is free software: you can redistribute it and/or modify
+//!   it under the terms of the GNU Affero General Public License as published by
+//!   the Free Software Foundation, either version 3 of the License, or
+//!   (at your option) any later version.
+//!
+//!   This program is distributed in the hope that it will be useful,
+//!   but WITHOUT ANY WARRANTY; without even the implied warranty of
+//!   MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//!   GNU Affero General Public License for more details.
+//!
+//!   You should have received a copy of the GNU Affero General Public License
+//!   along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+
+/// Writes the generated files under `directory` and removes the ones that are no longer generated.
+pub(crate) fn write_files(directory: &str, files: &BTreeMap<String, String>) -> Result<()> {
+  let legacy: String = format!("{directory}.rs");
+  if Path::new(&legacy).exists() {
+    fs::remove_file(&legacy).context(format!("ERROR: Failed to remove {legacy}"))?;
+  }
+
+  for existing in list_files(Path::new(directory))? {
+    let relative: &Path = existing.strip_prefix(directory)?;
+    if !files.contains_key(&relative.to_string_lossy().replace('\\', "/")) {
+      fs::remove_file(&existing).context(format!("ERROR: Failed to remove {}", existing.display()))?;
+    }
+  }
+
+  for (path, content) in files {
+    let path: PathBuf = Path::new(directory).join(path);
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+    write_if_changed(&path, content)?;
+  }
+
+  Ok(())
+}
+
+
+pub(crate) fn read_files(directory: &str) -> Result<String> {
+  let mut result: String = String::new();
+
+  for path in list_files(Path::new(directory))? {
+    result.push_str(&fs::read_to_string(&path)?);
+  }
+
+  Ok(result)
+}
+
+
+/// Writes `content` to `path` unless the file already has it, so unchanged files keep their timestamps.
+pub(crate) fn write_if_changed(path: &Path, content: &str) -> Result<()> {
+  if fs::read_to_string(path).is_ok_and(|existing: String| existing == content) {
+    return Ok(());
+  }
+
+  fs::write(path, content).context(format!("ERROR: Failed to write {}", path.display()))
+}
+
+
+fn list_files(directory: &Path) -> Result<Vec<PathBuf>> {
+  let mut result: Vec<PathBuf> = Vec::new();
+  if !directory.is_dir() {
+    return Ok(result);
+  }
+
+  for entry in fs::read_dir(directory)? {
+    let path: PathBuf = entry?.path();
+    if path.is_dir() {
+      result.extend(list_files(&path)?);
+    } else if path.extension().is_some_and(|extension: &std::ffi::OsStr| extension == "rs") {
+      result.push(path);
+    }
+  }
+
+  result.sort();
+  Ok(result)
+}
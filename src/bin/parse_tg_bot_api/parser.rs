@@ -15,22 +15,40 @@
 //!   along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 
-use std::collections::{HashSet, HashMap, BTreeSet};
+use std::collections::{HashSet, HashMap};
 
 use anyhow::{bail, Context, Result};
-use rayon::prelude::*;
 use select::{
   document::Document,
   node::Node,
-  predicate::{Attr, Class},
+  predicate::{Attr, Class, Name, Predicate},
 };
-use serde::de::value;
 
-use crate::tg_api::{Type, Method, Field};
+use crate::tg_api::{Type, Method, Field, Parameter, Version};
 
 
+const IGNORED_TAGS: [&str; 5] = ["blockquote", "div", "hr", "ol", "pre"];
+
+const PRIMITIVE_TYPES: [&str; 4] = ["bool", "f64", "i64", "String"];
+
+const COLUMN_ALIASES: [(&str, &str); 9] = [
+  ("field", "Name"),
+  ("fields", "Name"),
+  ("parameter", "Name"),
+  ("parameters", "Name"),
+  ("name", "Name"),
+  ("type", "Type"),
+  ("types", "Type"),
+  ("required", "Required"),
+  ("description", "Description"),
+];
+
+
+#[allow(clippy::enum_variant_names)]
 pub(crate) enum Tag {
+  H3Tag(H3Tag),
   H4Tag(H4Tag),
+  SectionTag(SectionTag),
   PTag(PTag),
   TableTag(TableTag),
   UlTag(UlTag),
@@ -44,8 +62,9 @@ impl Default for Tag {
 }
 
 
-pub(crate) fn get_list_of_main_tags(document: &Document) -> Result<Vec<Tag>> {
+pub(crate) fn get_list_of_main_tags(document: &Document, anomalies: &mut Vec<String>) -> Result<Vec<Tag>> {
   let mut result: Vec<Tag> = Vec::new();
+  let mut subsection: Option<String> = None;
   let document: Node = document.find(Attr("id", "dev_page_content")).next().context("ERROR: Couldn't find the start tag of the data")?;
 
   for node in document.children() {
@@ -55,26 +74,46 @@ pub(crate) fn get_list_of_main_tags(document: &Document) -> Result<Vec<Tag>> {
     };
 
     let tag: Tag = match node_name {
+      "h3" => Tag::H3Tag(parse_tag_h3(&node)),
+
       "h4" => {
-        if node.text().contains(" ") {
-          continue
+        if node.text().trim().contains(' ') {
+          Tag::SectionTag(parse_tag_section(&node))
+        } else {
+          Tag::H4Tag(parse_tag_h4(&node))
         }
-        Tag::H4Tag(parse_tag_h4(&node))
       },
 
       "p" => Tag::PTag(parse_tag_p(&node)),
 
       "table" => {
         if node.attr("class").context("ERROR: The table tag does not have the class attribute")? != "table" {
+          anomalies.push(String::from("Table without the \"table\" class"));
+          continue
+        }
+        if let Some(subsection) = &subsection {
+          anomalies.push(format!("Table under the \"{subsection}\" section"));
           continue
         }
-        Tag::TableTag(parse_tag_table(&node)?)
+        Tag::TableTag(parse_tag_table(&node, anomalies)?)
       },
 
       "ul" => Tag::UlTag(parse_tag_ul(&node)?),
-      _ => continue,
+
+      _ => {
+        if !IGNORED_TAGS.contains(&node_name) {
+          anomalies.push(format!("Unexpected <{node_name}> tag"));
+        }
+        continue
+      },
     };
 
+    match &tag {
+      Tag::H3Tag(_) | Tag::H4Tag(_) => subsection = None,
+      Tag::SectionTag(tag) => subsection = Some(tag.value.clone()),
+      _ => (),
+    }
+
     result.push(tag);
   }
 
@@ -82,25 +121,52 @@ pub(crate) fn get_list_of_main_tags(document: &Document) -> Result<Vec<Tag>> {
 }
 
 
-pub(crate) fn parse_api(tags: &Vec<Tag>) -> Result<(HashSet<Type>, HashSet<Method>)> {
-  let (types, methods): (Result<HashSet<Type>>, HashSet<Method>) = rayon::join(
-    || -> Result<HashSet<Type>> { Ok(parse_types(tags)?) },
-    || -> HashSet<Method> { parse_methods(tags) },
+pub(crate) fn parse_api(tags: &[Tag]) -> Result<(HashSet<Type>, HashSet<Method>)> {
+  let (types, methods): (Result<HashSet<Type>>, Result<HashSet<Method>>) = rayon::join(
+    || -> Result<HashSet<Type>> { parse_types(tags) },
+    || -> Result<HashSet<Method>> { parse_methods(tags) },
   );
-  Ok((types?, methods))
+  let types: HashSet<Type> = types?;
+
+  let type_names: HashSet<&str> = types.iter().map(|r#type: &Type| r#type.name.as_str()).collect();
+  let methods: HashSet<Method> = methods?.into_iter()
+    .map(|mut method: Method| {
+      method.return_type = parse_return_type(&method.description, &type_names);
+      method
+    })
+    .collect();
+
+  Ok((types, methods))
+}
+
+
+#[derive(Clone)]
+pub(crate) struct H3Tag {
+  pub(crate) value: String,
+}
+
+
+impl H3Tag {
+  fn new(value: String) -> Self {
+    Self {
+      value,
+    }
+  }
 }
 
 
 #[derive(Clone)]
 pub(crate) struct H4Tag {
   pub(crate) value: String,
+  pub(crate) anchor: String,
 }
 
 
 impl H4Tag {
-  fn new(value: String) -> Self {
+  fn new(value: String, anchor: String) -> Self {
     Self {
       value,
+      anchor,
     }
   }
 }
@@ -108,7 +174,24 @@ impl H4Tag {
 
 impl Default for H4Tag {
   fn default() -> Self {
-    Self::new(String::default())
+    Self::new(String::default(), String::default())
+  }
+}
+
+
+#[derive(Clone)]
+pub(crate) struct SectionTag {
+  pub(crate) value: String,
+  pub(crate) anchor: String,
+}
+
+
+impl SectionTag {
+  fn new(value: String, anchor: String) -> Self {
+    Self {
+      value,
+      anchor,
+    }
   }
 }
 
@@ -160,12 +243,12 @@ impl LineTag {
 
 #[derive(Clone)]
 pub(crate) struct UlTag {
-  pub(crate) list_items: HashSet<LiTag>,
+  pub(crate) list_items: Vec<LiTag>,
 }
 
 
 impl UlTag {
-  pub(crate) fn new(list_items: HashSet<LiTag>) -> Self {
+  pub(crate) fn new(list_items: Vec<LiTag>) -> Self {
     Self {
       list_items,
     }
@@ -188,17 +271,50 @@ impl LiTag {
 }
 
 
+fn parse_tag_h3(node: &Node) -> H3Tag {
+  H3Tag::new(node.text().trim().to_string())
+}
+
+
 fn parse_tag_h4(node: &Node) -> H4Tag {
-  H4Tag::new(node.text())
+  let value: String = node.text();
+  let anchor: String = parse_anchor(node, &value);
+
+  H4Tag::new(value, anchor)
+}
+
+
+fn parse_tag_section(node: &Node) -> SectionTag {
+  let value: String = node.text().trim().to_string();
+  let anchor: String = parse_anchor(node, &value);
+
+  SectionTag::new(value, anchor)
+}
+
+
+fn parse_anchor(node: &Node, value: &str) -> String {
+  match node.find(Class("anchor")).next().and_then(|anchor: Node| anchor.attr("name")) {
+    Some(anchor) => anchor.to_string(),
+    None => value.trim().to_lowercase(),
+  }
 }
 
 
 fn parse_tag_p(node: &Node) -> PTag {
+  if is_struck_through(node) {
+    return PTag::new(format!("Deprecated. {}", node.text()));
+  }
+
   PTag::new(node.text())
 }
 
 
-fn parse_tag_table(node: &Node) -> Result<TableTag> {
+fn is_struck_through(node: &Node) -> bool {
+  node.find(Name("del").or(Name("s"))).next().is_some()
+}
+
+
+fn parse_tag_table(node: &Node, anomalies: &mut Vec<String>) -> Result<TableTag> {
   let mut column_names: Vec<String> = Vec::new();
   let mut lines: Vec<LineTag> = Vec::new();
 
@@ -210,17 +326,27 @@ fn parse_tag_table(node: &Node) -> Result<TableTag> {
 
     match tag_name {
       "thead" => column_names = parse_table_thead(&tag)?,
-      "tbody" => lines = parse_table_tbody(&tag, &column_names)?,
+      "tbody" => lines = parse_table_tbody(&tag, &column_names, anomalies)?,
       _ => (),
     }
   }
 
+  if column_names.is_empty() {
+    anomalies.push(String::from("Table without a header"));
+  }
+  for line in &lines {
+    if line.value.len() < column_names.len() {
+      let name: &str = column_names.first().and_then(|column: &String| line.value.get(column)).map_or("", String::as_str);
+      anomalies.push(format!("Table row \"{name}\" has {} of {} columns", line.value.len(), column_names.len()));
+    }
+  }
+
   Ok(TableTag::new(lines))
 }
 
 
 fn parse_tag_ul(node: &Node) -> Result<UlTag> {
-  let mut list_items: HashSet<LiTag> = HashSet::new();
+  let mut list_items: Vec<LiTag> = Vec::new();
 
   for tag in node.children() {
     let tag_name: &str = match tag.name() {
@@ -232,7 +358,7 @@ fn parse_tag_ul(node: &Node) -> Result<UlTag> {
       continue;
     }
 
-    list_items.insert(LiTag::new(tag.text().trim().to_string()));
+    list_items.push(LiTag::new(tag.text().trim().to_string()));
   }
 
   Ok(UlTag::new(list_items))
@@ -262,7 +388,7 @@ fn parse_table_thead(node: &Node) -> Result<Vec<String>> {
         continue;
       }
 
-      result.push(column.text().trim().to_string());
+      result.push(canonical_column(column.text().trim()));
     }
   }
 
@@ -270,7 +396,15 @@ fn parse_table_thead(node: &Node) -> Result<Vec<String>> {
 }
 
 
-fn parse_table_tbody(node: &Node, column_name: &Vec<String>) -> Result<Vec<LineTag>> {
+fn canonical_column(header: &str) -> String {
+  match COLUMN_ALIASES.iter().find(|(alias, _): &&(&str, &str)| header.eq_ignore_ascii_case(alias)) {
+    Some((_, name)) => name.to_string(),
+    None => header.to_string(),
+  }
+}
+
+
+fn parse_table_tbody(node: &Node, column_names: &[String], anomalies: &mut Vec<String>) -> Result<Vec<LineTag>> {
   let mut result: Vec<LineTag> = Vec::new();
 
   for tag in node.children() {
@@ -295,10 +429,21 @@ fn parse_table_tbody(node: &Node, column_name: &Vec<String>) -> Result<Vec<LineT
         continue;
       }
 
-      line.insert(column_name[idx].clone(), field.text().trim().to_string());
+      match column_names.get(idx) {
+        Some(column_name) => {
+          line.insert(column_name.clone(), field.text().trim().to_string());
+        },
+        None => anomalies.push(format!("Table row has an extra cell \"{}\"", field.text().trim())),
+      }
       idx += 1;
     }
 
+    if is_struck_through(&tag) {
+      if let Some(description) = line.get_mut("Description") {
+        *description = format!("{}. Deprecated.", description.trim_end_matches('.'));
+      }
+    }
+
     result.push(LineTag::new(line));
   }
 
@@ -306,51 +451,174 @@ fn parse_table_tbody(node: &Node, column_name: &Vec<String>) -> Result<Vec<LineT
 }
 
 
-fn parse_types(tags: &Vec<Tag>) -> Result<HashSet<Type>> {
+/// Collects the API versions listed as "Bot API X.Y" paragraphs under dated headers, newest first.
+pub(crate) fn parse_versions(tags: &[Tag]) -> Vec<Version> {
+  let mut result: Vec<Version> = Vec::new();
+  let mut date: Option<&SectionTag> = None;
+  let mut in_version: bool = false;
+
+  for tag in tags {
+    match tag {
+      Tag::H3Tag(_) | Tag::H4Tag(_) => {
+        date = None;
+        in_version = false;
+      },
+
+      Tag::SectionTag(tag) => {
+        date = Some(tag);
+        in_version = false;
+      },
+
+      Tag::PTag(tag) => {
+        if let (Some(date), Some(name)) = (date, tag.value.trim().strip_prefix("Bot API ")) {
+          result.push(Version::new(name.to_string(), date.value.clone(), date.anchor.clone(), Vec::new()));
+          in_version = true;
+        }
+      },
+
+      Tag::UlTag(tag) => {
+        let version: Option<&mut Version> = if in_version { result.last_mut() } else { None };
+        if let Some(version) = version {
+          version.changes.extend(tag.list_items.iter().map(|li: &LiTag| li.value.split_whitespace().collect::<Vec<&str>>().join(" ")));
+        }
+      },
+
+      Tag::TableTag(_) => (),
+    }
+  }
+
+  result
+}
+
+
+pub(crate) fn validate_types(types: &HashSet<Type>, methods: &HashSet<Method>) -> Vec<String> {
+  let mut result: Vec<String> = Vec::new();
+  let type_names: HashSet<&str> = types.iter().map(|r#type: &Type| r#type.name.as_str()).collect();
+
+  for r#type in types {
+    for field in &r#type.fields {
+      if field.name == field.r#type {
+        continue;
+      }
+      if let Some(anomaly) = validate_type(&field.r#type, &type_names) {
+        result.push(format!("{anomaly} of {}.{}", r#type.name, field.name));
+      }
+    }
+  }
+
+  for method in methods {
+    for parameter in &method.parameters {
+      if let Some(anomaly) = validate_type(&parameter.r#type, &type_names) {
+        result.push(format!("{anomaly} of {}.{}", method.name, parameter.name));
+      }
+    }
+
+    if method.return_type.is_empty() {
+      result.push(format!("Missing return type of {}", method.name));
+    }
+  }
+
+  result.sort();
+  result
+}
+
+
+fn validate_type(r#type: &str, type_names: &HashSet<&str>) -> Option<String> {
+  let mut inner: &str = r#type;
+  while let Some(element) = inner.strip_prefix("Vec<").and_then(|element: &str| element.strip_suffix('>')) {
+    inner = element;
+  }
+
+  let parts: Vec<&str> = inner.split(" or ")
+    .flat_map(|part: &str| part.split(", "))
+    .flat_map(|part: &str| part.split(" and "))
+    .collect();
+
+  if !parts.iter().all(|part: &&str| !part.is_empty() && part.chars().all(char::is_alphanumeric)) {
+    return Some(format!("Unmapped type \"{type}\""));
+  }
+
+  let unresolved: Vec<&str> = parts.into_iter()
+    .filter(|part: &&str| !PRIMITIVE_TYPES.contains(part) && !type_names.contains(part))
+    .collect();
+  if !unresolved.is_empty() {
+    return Some(format!("Unresolved type \"{}\"", unresolved.join(", ")));
+  }
+
+  None
+}
+
+
+fn parse_types(tags: &[Tag]) -> Result<HashSet<Type>> {
   let mut result: HashSet<Type> = HashSet::new();
 
   let mut prev_tag: Tag = Tag::default();
+  let mut section: String = String::new();
   let mut type_name: String = String::new();
+  let mut type_anchor: String = String::new();
   let mut type_desc: String = String::new();
+  // Set once the type is emitted, so the notes that follow its table or list do not emit it again.
+  let mut emitted: bool = false;
   
   for tag in tags {
     match tag {
+      Tag::H3Tag(tag) => {
+        if let Tag::PTag(_) | Tag::UlTag(_) = prev_tag {
+          if !emitted && type_name.chars().next().is_some_and(char::is_uppercase) {
+            result.insert(parse_type(&type_name, &type_desc, &section, &type_anchor, None, None)?);
+          }
+        }
+
+        section = tag.value.clone();
+        type_name = String::new();
+        emitted = false;
+        prev_tag = Tag::H3Tag(tag.clone());
+      },
+
       Tag::H4Tag(tag) => {
-        if let Tag::PTag(_) = prev_tag {
-          match type_name.chars().next() {
-            Some(ch) => {
-              if ch.is_uppercase() {
-                result.insert(parse_type(&type_name, &type_desc, None, None)?);
-              }
-            },
-            None => (),
+        if let Tag::PTag(_) | Tag::UlTag(_) = prev_tag {
+          if !emitted && type_name.chars().next().is_some_and(char::is_uppercase) {
+            result.insert(parse_type(&type_name, &type_desc, &section, &type_anchor, None, None)?);
           }
         }
 
         type_name = tag.value.clone();
+        type_anchor = tag.anchor.clone();
+        emitted = false;
         prev_tag = Tag::H4Tag(tag.clone());
       },
 
+      Tag::SectionTag(tag) => {
+        if let Tag::PTag(_) | Tag::UlTag(_) = prev_tag {
+          if !emitted && type_name.chars().next().is_some_and(char::is_uppercase) {
+            result.insert(parse_type(&type_name, &type_desc, &section, &type_anchor, None, None)?);
+          }
+        }
+
+        type_name = String::new();
+        emitted = false;
+        prev_tag = Tag::SectionTag(tag.clone());
+      },
+
       Tag::PTag(tag) => {
-        type_desc = tag.value.clone();
+        if !emitted {
+          type_desc = tag.value.clone();
+        }
         prev_tag = Tag::PTag(tag.clone());
       },
 
       Tag::TableTag(tag) => {
-        if type_name.chars().next().context("ERROR: Empty type name")?.is_uppercase() {
-          result.insert(parse_type(&type_name, &type_desc, Some(tag), None)?);
+        if !emitted && type_name.chars().next().is_some_and(char::is_uppercase) {
+          result.insert(parse_type(&type_name, &type_desc, &section, &type_anchor, Some(tag), None)?);
+          emitted = true;
         }
         prev_tag = Tag::TableTag(tag.clone());
       },
 
       Tag::UlTag(tag) => {
-        match type_name.chars().next() {
-          Some(ch) => {
-            if ch.is_uppercase() {
-              result.insert(parse_type(&type_name, &type_desc, None, Some(tag))?);
-            }
-          },
-          None => (),
+        if !emitted && is_type_list(tag) && type_name.chars().next().is_some_and(char::is_uppercase) {
+          result.insert(parse_type(&type_name, &type_desc, &section, &type_anchor, None, Some(tag))?);
+          emitted = true;
         }
         prev_tag = Tag::UlTag(tag.clone());
       },
@@ -361,19 +629,104 @@ fn parse_types(tags: &Vec<Tag>) -> Result<HashSet<Type>> {
 }
 
 
-fn parse_methods(tags: &Vec<Tag>) -> HashSet<Method> {
-  HashSet::new()
+/// Whether the list names the variants of a type ("It can be one of ...") rather than being a list of notes.
+fn is_type_list(ul: &UlTag) -> bool {
+  ul.list_items.iter().all(|li: &LiTag| li.value.chars().next().is_some_and(char::is_uppercase) && !li.value.contains(char::is_whitespace))
 }
 
 
-fn parse_type(name: &String, desc: &String, table: Option<&TableTag>, ul: Option<&UlTag>) -> Result<Type> {
+fn parse_methods(tags: &[Tag]) -> Result<HashSet<Method>> {
+  let mut result: HashSet<Method> = HashSet::new();
+
+  let mut prev_tag: Tag = Tag::default();
+  let mut section: String = String::new();
+  let mut method_name: String = String::new();
+  let mut method_anchor: String = String::new();
+  let mut method_desc: String = String::new();
+  // Set once the method is emitted, so the notes that follow its table do not emit it again.
+  let mut emitted: bool = false;
+
+  for tag in tags {
+    match tag {
+      Tag::H3Tag(tag) => {
+        if let Tag::PTag(_) = prev_tag {
+          if !emitted && method_name.chars().next().is_some_and(char::is_lowercase) {
+            result.insert(parse_method(&method_name, &method_desc, &section, &method_anchor, None)?);
+          }
+        }
+
+        section = tag.value.clone();
+        method_name = String::new();
+        emitted = false;
+        prev_tag = Tag::H3Tag(tag.clone());
+      },
+
+      Tag::H4Tag(tag) => {
+        if let Tag::PTag(_) = prev_tag {
+          if !emitted && method_name.chars().next().is_some_and(char::is_lowercase) {
+            result.insert(parse_method(&method_name, &method_desc, &section, &method_anchor, None)?);
+          }
+        }
+
+        method_name = tag.value.clone();
+        method_anchor = tag.anchor.clone();
+        emitted = false;
+        prev_tag = Tag::H4Tag(tag.clone());
+      },
+
+      Tag::SectionTag(tag) => {
+        if let Tag::PTag(_) = prev_tag {
+          if !emitted && method_name.chars().next().is_some_and(char::is_lowercase) {
+            result.insert(parse_method(&method_name, &method_desc, &section, &method_anchor, None)?);
+          }
+        }
+
+        method_name = String::new();
+        emitted = false;
+        prev_tag = Tag::SectionTag(tag.clone());
+      },
+
+      Tag::PTag(tag) => {
+        if let Tag::H4Tag(_) = prev_tag {
+          method_desc = tag.value.clone();
+        }
+        prev_tag = Tag::PTag(tag.clone());
+      },
+
+      Tag::TableTag(tag) => {
+        if !emitted && method_name.chars().next().is_some_and(char::is_lowercase) {
+          result.insert(parse_method(&method_name, &method_desc, &section, &method_anchor, Some(tag))?);
+          emitted = true;
+        }
+        prev_tag = Tag::TableTag(tag.clone());
+      },
+
+      Tag::UlTag(tag) => prev_tag = Tag::UlTag(tag.clone()),
+    }
+  }
+
+  Ok(result)
+}
+
+
+fn parse_method(name: &str, desc: &str, section: &str, anchor: &str, table: Option<&TableTag>) -> Result<Method> {
+  let parameters: Vec<Parameter> = match table {
+    Some(table) => get_parameters_from_table(table)?,
+    None => Vec::new(),
+  };
+
+  Ok(Method::new(name.to_string(), desc.to_string(), section.to_string(), anchor.to_string(), parameters, String::new()))
+}
+
+
+fn parse_type(name: &str, desc: &str, section: &str, anchor: &str, table: Option<&TableTag>, ul: Option<&UlTag>) -> Result<Type> {
   if table.is_some() && ul.is_some() {
     bail!("ERROR: Type can only have one of 'table' or 'ul'");
   }
 
-  let mut fields: BTreeSet<Field> = match table {
+  let mut fields: Vec<Field> = match table {
     Some(table) => get_fields_from_table(table)?,
-    None => BTreeSet::new(),
+    None => Vec::new(),
   };
 
   fields = match ul {
@@ -381,41 +734,63 @@ fn parse_type(name: &String, desc: &String, table: Option<&TableTag>, ul: Option
     None => fields,
   };
   
-  Ok(Type::new(name.clone(), desc.clone(), fields))
+  Ok(Type::new(name.to_string(), desc.to_string(), section.to_string(), anchor.to_string(), fields))
 }
 
 
-fn get_fields_from_table(table: &TableTag) -> Result<BTreeSet<Field>> {
-  let mut result: BTreeSet<Field> = BTreeSet::new();
+fn get_fields_from_table(table: &TableTag) -> Result<Vec<Field>> {
+  let mut result: Vec<Field> = Vec::new();
 
   for line in &table.lines {
-    let name: String = line.value.get("Field").context("ERROR: The field did not have a name found")?.clone();
+    let name: String = line.value.get("Name").context("ERROR: The field did not have a name found")?.clone();
     let r#type: String = line.value.get("Type").context("ERROR: The field type was not found")?.clone();
     let description: String = line.value.get("Description").context("ERROR: No description found for the field")?.clone();
 
     let r#type: String = parse_field_type(&r#type);
 
-    result.insert(Field::new(name, r#type, description.starts_with("Optional"), description));
+    result.push(Field::new(name, r#type, description.starts_with("Optional"), description));
   }
 
   Ok(result)
 }
 
 
-fn get_fields_from_ul(ul: &UlTag) -> Result<BTreeSet<Field>> {
-  let mut result: BTreeSet<Field> = BTreeSet::new();
+fn get_parameters_from_table(table: &TableTag) -> Result<Vec<Parameter>> {
+  let mut result: Vec<Parameter> = Vec::new();
+
+  for line in &table.lines {
+    let name: String = line.value.get("Name").context("ERROR: The parameter did not have a name found")?.clone();
+    let r#type: String = line.value.get("Type").context("ERROR: The parameter type was not found")?.clone();
+    let description: String = line.value.get("Description").context("ERROR: No description found for the parameter")?.clone();
+    let required: bool = match line.value.get("Required") {
+      Some(required) => required == "Yes",
+      None => !description.starts_with("Optional"),
+    };
+
+    let r#type: String = parse_field_type(&r#type);
+
+    result.push(Parameter::new(name, r#type, required, description));
+  }
+
+  Ok(result)
+}
+
+
+fn get_fields_from_ul(ul: &UlTag) -> Result<Vec<Field>> {
+  let mut result: Vec<Field> = Vec::new();
 
   for li in &ul.list_items {
-    result.insert(Field::new(li.value.clone(), li.value.clone(), false, String::from("")));
+    result.push(Field::new(li.value.clone(), li.value.clone(), false, String::from("")));
   }
 
   Ok(result)
 }
 
 
-fn parse_field_type(type_name: &String) -> String {
-  if type_name.trim().starts_with("Array of") {
-    return format!("Vec<{}>", parse_field_type(&type_name.split_at("Array of".len()).1.trim().to_string()));
+fn parse_field_type(type_name: &str) -> String {
+  let type_name: &str = type_name.trim();
+  if let Some(element) = type_name.strip_prefix("Array of") {
+    return format!("Vec<{}>", parse_field_type(element));
   }
 
   let tg_types: HashMap<String, String> = HashMap::from([
@@ -429,6 +804,120 @@ fn parse_field_type(type_name: &String) -> String {
 
   match tg_types.get(type_name) {
     Some(r#type) => r#type.clone(),
-    None => type_name.clone(),
+    None => type_name.to_string(),
+  }
+}
+
+
+fn parse_return_type(description: &str, type_names: &HashSet<&str>) -> String {
+  let mut result: Vec<String> = Vec::new();
+
+  for sentence in description.split(". ") {
+    if !sentence.to_lowercase().contains("return") {
+      continue;
+    }
+
+    let words: Vec<&str> = sentence.split_whitespace()
+      .map(|word: &str| word.trim_matches(|ch: char| !ch.is_alphanumeric()))
+      .collect();
+
+    let mut idx: usize = 0;
+    while idx < words.len() {
+      let mut depth: usize = 0;
+      while words.get(idx).is_some_and(|word: &&str| word.eq_ignore_ascii_case("array")) && words.get(idx + 1) == Some(&"of") {
+        depth += 1;
+        idx += 2;
+      }
+
+      let word: &str = match words.get(idx) {
+        Some(word) => word,
+        None => break,
+      };
+
+      let r#type: Option<&str> = match word {
+        "True" | "String" => Some(word),
+        "Int" => Some("Integer"),
+        _ if type_names.contains(word) => Some(word),
+        _ => word.strip_suffix('s').filter(|word: &&str| type_names.contains(word)),
+      };
+
+      if let Some(r#type) = r#type {
+        let r#type: String = format!("{}{type}", "Array of ".repeat(depth));
+
+        if !result.contains(&r#type) {
+          result.push(r#type);
+        }
+      }
+
+      idx += 1;
+    }
+  }
+
+  parse_field_type(&result.join(" or "))
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+
+  #[test]
+  fn nested_array_field_type() {
+    assert_eq!(parse_field_type("Array of Array of PhotoSize"), "Vec<Vec<PhotoSize>>");
+  }
+
+
+  #[test]
+  fn triple_nested_array_field_type() {
+    assert_eq!(parse_field_type("Array of Array of Array of Integer"), "Vec<Vec<Vec<i64>>>");
+  }
+
+
+  fn h4(name: &str) -> Tag {
+    Tag::H4Tag(H4Tag::new(name.to_string(), name.to_lowercase()))
+  }
+
+
+  fn p(text: &str) -> Tag {
+    Tag::PTag(PTag::new(text.to_string()))
+  }
+
+
+  fn table(rows: &[[&str; 4]]) -> Tag {
+    let lines: Vec<LineTag> = rows.iter()
+      .map(|row: &[&str; 4]| {
+        let value: HashMap<String, String> = ["Name", "Type", "Required", "Description"].iter()
+          .zip(row)
+          .map(|(column, cell): (&&str, &&str)| (column.to_string(), cell.to_string()))
+          .collect();
+        LineTag::new(value)
+      })
+      .collect();
+    Tag::TableTag(TableTag::new(lines))
+  }
+
+
+  #[test]
+  fn notes_after_method_table() {
+    let tags: Vec<Tag> = vec![
+      Tag::H3Tag(H3Tag::new(String::from("Getting updates"))),
+      h4("getUpdates"),
+      p("Use this method to receive incoming updates. Returns an Array of Update objects."),
+      table(&[["offset", "Integer", "Optional", "Identifier of the first update to be returned"]]),
+      p("Notes 1. This method will not work if an outgoing webhook is set up."),
+      h4("getMe"),
+      p("A simple method for testing your bot's authentication token. Returns basic information about the bot."),
+      Tag::H3Tag(H3Tag::new(String::from("Available types"))),
+    ];
+
+    let methods: HashSet<Method> = parse_methods(&tags).unwrap();
+    let mut names: Vec<&str> = methods.iter().map(|method: &Method| method.name.as_str()).collect();
+    names.sort();
+    assert_eq!(names, ["getMe", "getUpdates"]);
+
+    let get_updates: &Method = methods.iter().find(|method: &&Method| method.name == "getUpdates").unwrap();
+    assert_eq!(get_updates.parameters.len(), 1);
+    assert!(get_updates.description.starts_with("Use this method"));
   }
 }
@@ -0,0 +1,50 @@
+//!   Rust telegram bot api. The library provides asynchronous access to the telegram bot api.
+//!   Copyright (C) 2024  Andrew Kozmin
+//!
+//!   This program is free software: you can redistribute it and/or modify
+//!   it under the terms of the GNU Affero General Public License as published by
+//!   the Free Software Foundation, either version 3 of the License, or
+//!   (at your option) any later version.
+//!
+//!   This program is distributed in the hope that it will be useful,
+//!   but WITHOUT ANY WARRANTY; without even the implied warranty of
+//!   MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//!   GNU Affero General Public License for more details.
+//!
+//!   You should have received a copy of the GNU Affero General Public License
+//!   along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+
+use std::io::Write;
+use std::process::{Child, Command, Output, Stdio};
+
+use anyhow::{bail, Context, Result};
+
+
+const CONFIG: &str = "tab_spaces=2,blank_lines_upper_bound=2";
+
+
+pub(crate) fn format(api: String) -> Result<String> {
+  let mut child: Child = match Command::new("rustfmt")
+    .args(["--edition", "2021", "--emit", "stdout", "--config", CONFIG])
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+  {
+    Ok(child) => child,
+    Err(e) => {
+      eprintln!("WARNING: rustfmt is not available, the api is left unformatted: {e}");
+      return Ok(api);
+    },
+  };
+
+  child.stdin.take().context("ERROR: Failed to open rustfmt stdin")?.write_all(api.as_bytes())?;
+  let output: Output = child.wait_with_output()?;
+
+  if !output.status.success() {
+    bail!("ERROR: rustfmt failed:\n{}", String::from_utf8_lossy(&output.stderr));
+  }
+
+  Ok(String::from_utf8(output.stdout)?)
+}
@@ -0,0 +1,203 @@
+//!   Rust telegram bot api. The library provides asynchronous access to the telegram bot api.
+//!   Copyright (C) 2024  Andrew Kozmin
+//!
+//!   This program is free software: you can redistribute it and/or modify
+//!   it under the terms of the GNU Affero General Public License as published by
+//!   the Free Software Foundation, either version 3 of the License, or
+//!   (at your option) any later version.
+//!
+//!   This program is distributed in the hope that it will be useful,
+//!   but WITHOUT ANY WARRANTY; without even the implied warranty of
+//!   MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//!   GNU Affero General Public License for more details.
+//!
+//!   You should have received a copy of the GNU Affero General Public License
+//!   along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::output::write_if_changed;
+use crate::tg_api::{Type, Method, Field, Parameter};
+
+
+#[derive(Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Snapshot {
+  types: BTreeMap<String, Vec<Type>>,
+  methods: BTreeMap<String, Method>,
+}
+
+
+impl Snapshot {
+  pub(crate) fn new(types: &HashSet<Type>, methods: &HashSet<Method>) -> Self {
+    let mut result: Self = Self::default();
+
+    for r#type in types {
+      result.types.entry(r#type.name.clone()).or_default().push(r#type.clone());
+    }
+    for types in result.types.values_mut() {
+      types.sort();
+    }
+
+    for method in methods {
+      result.methods.insert(method.name.clone(), method.clone());
+    }
+
+    result
+  }
+
+
+  pub(crate) fn load(path: &str) -> Result<Option<Self>> {
+    if !Path::new(path).exists() {
+      return Ok(None);
+    }
+
+    let snapshot: String = fs::read_to_string(path)?;
+    Ok(Some(serde_json::from_str(&snapshot).context(format!("ERROR: Invalid api snapshot {path}"))?))
+  }
+
+
+  pub(crate) fn save(&self, path: &str) -> Result<()> {
+    write_if_changed(Path::new(path), &serde_json::to_string_pretty(self)?)
+  }
+
+
+  /// Lists the types and methods added (`+`), removed (`-`) or changed (`~`) since `previous`.
+  pub(crate) fn changes(&self, previous: &Self) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    changes(&self.types, &previous.types, &mut result);
+    changes(&self.methods, &previous.methods, &mut result);
+    result
+  }
+
+
+  /// Renders a Markdown summary of the changes since `previous` for the release notes, if there are any.
+  pub(crate) fn report(&self, previous: &Self) -> Option<String> {
+    let mut result: String = String::new();
+    let changed: BTreeSet<&str> = changed_names(&self.types, &previous.types);
+    report(&mut result, "types", &type_members(&self.types), &type_members(&previous.types), &changed);
+    let changed: BTreeSet<&str> = changed_names(&self.methods, &previous.methods);
+    report(&mut result, "methods", &method_members(&self.methods), &method_members(&previous.methods), &changed);
+    if result.is_empty() {
+      return None;
+    }
+
+    Some(format!("# API changes\n{result}"))
+  }
+}
+
+
+type Members<'a> = BTreeMap<&'a str, BTreeMap<&'a str, String>>;
+
+
+/// Collects the fields of every definition of a type, the same data `changes` compares.
+fn type_members(types: &BTreeMap<String, Vec<Type>>) -> Members<'_> {
+  types.iter()
+    .map(|(name, types): (&String, &Vec<Type>)| {
+      let fields: BTreeMap<&str, String> = types.iter()
+        .flat_map(|r#type: &Type| &r#type.fields)
+        .map(|field: &Field| (field.name.as_str(), format!("{}{}", field.r#type, if field.optional { ", optional" } else { "" })))
+        .collect();
+      (name.as_str(), fields)
+    })
+    .collect()
+}
+
+
+fn method_members(methods: &BTreeMap<String, Method>) -> Members<'_> {
+  methods.iter()
+    .map(|(name, method): (&String, &Method)| {
+      let parameters: BTreeMap<&str, String> = method.parameters.iter()
+        .map(|parameter: &Parameter| (parameter.name.as_str(), format!("{}{}", parameter.r#type, if parameter.required { "" } else { ", optional" })))
+        .collect();
+      (name.as_str(), parameters)
+    })
+    .collect()
+}
+
+
+fn report(result: &mut String, kind: &str, current: &Members, previous: &Members, modified: &BTreeSet<&str>) {
+  let mut added: Vec<&str> = current.keys().filter(|name: &&&str| !previous.contains_key(*name)).copied().collect();
+  let mut removed: Vec<&str> = previous.keys().filter(|name: &&&str| !current.contains_key(*name)).copied().collect();
+
+  let mut renamed: Vec<String> = Vec::new();
+  for old in removed.clone() {
+    let new: Option<usize> = added.iter().position(|new: &&str| !previous[old].is_empty() && previous[old] == current[new]);
+    if let Some(new) = new {
+      renamed.push(format!("`{old}` → `{}`", added.remove(new)));
+      removed.retain(|name: &&str| *name != old);
+    }
+  }
+
+  let mut changed: Vec<String> = Vec::new();
+  for (name, members) in current {
+    let old_members: &BTreeMap<&str, String> = match previous.get(name) {
+      Some(old_members) if modified.contains(name) => old_members,
+      _ => continue,
+    };
+
+    let mut details: Vec<String> = Vec::new();
+    for (member, signature) in members {
+      match old_members.get(member) {
+        None => details.push(format!("added `{member}` ({signature})")),
+        Some(old_signature) if old_signature != signature => details.push(format!("`{member}` is now {signature} (was {old_signature})")),
+        _ => (),
+      }
+    }
+    for member in old_members.keys().filter(|member: &&&str| !members.contains_key(*member)) {
+      details.push(format!("removed `{member}`"));
+    }
+
+    if details.is_empty() {
+      details.push(String::from("documentation changed"));
+    }
+    changed.push(format!("`{name}`: {}", details.join(", ")));
+  }
+
+  let added: Vec<String> = added.iter().map(|name: &&str| format!("`{name}`")).collect();
+  let removed: Vec<String> = removed.iter().map(|name: &&str| format!("`{name}`")).collect();
+  report_section(result, &format!("New {kind}"), &added);
+  report_section(result, &format!("Removed {kind}"), &removed);
+  report_section(result, &format!("Renamed {kind}"), &renamed);
+  report_section(result, &format!("Changed {kind}"), &changed);
+}
+
+
+fn report_section(result: &mut String, title: &str, items: &[String]) {
+  if items.is_empty() {
+    return;
+  }
+
+  result.push_str(&format!("\n## {title}\n\n"));
+  for item in items {
+    result.push_str(&format!("- {item}\n"));
+  }
+}
+
+
+fn changed_names<'a, T: PartialEq>(current: &'a BTreeMap<String, T>, previous: &BTreeMap<String, T>) -> BTreeSet<&'a str> {
+  current.iter()
+    .filter(|(name, value): &(&String, &T)| previous.get(*name).is_some_and(|previous: &T| previous != *value))
+    .map(|(name, _): (&String, &T)| name.as_str())
+    .collect()
+}
+
+
+fn changes<T: PartialEq>(current: &BTreeMap<String, T>, previous: &BTreeMap<String, T>, result: &mut Vec<String>) {
+  let names: BTreeSet<&String> = current.keys().chain(previous.keys()).collect();
+
+  for name in names {
+    match (current.get(name), previous.get(name)) {
+      (Some(_), None) => result.push(format!("+ {name}")),
+      (None, Some(_)) => result.push(format!("- {name}")),
+      (Some(current), Some(previous)) if current != previous => result.push(format!("~ {name}")),
+      _ => (),
+    }
+  }
+}
+
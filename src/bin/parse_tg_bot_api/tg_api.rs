@@ -15,36 +15,60 @@
 //!   along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 
-use std::collections::BTreeSet;
+use serde::{Deserialize, Serialize};
 
 
-#[derive(Debug, Eq, Hash, PartialEq)]
+#[derive(Debug, Clone, Eq, Hash, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
 pub(crate) struct Type {
   pub(crate) name: String,
   pub(crate) description: String,
-  pub(crate) fields: BTreeSet<Field>,
+  pub(crate) section: String,
+  #[serde(default)]
+  pub(crate) anchor: String,
+  pub(crate) fields: Vec<Field>,
 }
 
 
 impl Type {
-  pub(crate) fn new(name: String, description: String, fields: BTreeSet<Field>) -> Self {
+  pub(crate) fn new(name: String, description: String, section: String, anchor: String, fields: Vec<Field>) -> Self {
     Self {
       name,
       description,
+      section,
+      anchor,
       fields,
     }
   }
 }
 
 
+#[derive(Debug, Clone, Eq, Hash, PartialEq, Serialize, Deserialize)]
 pub(crate) struct Method {
   pub(crate) name: String,
   pub(crate) description: String,
+  pub(crate) section: String,
+  #[serde(default)]
+  pub(crate) anchor: String,
   pub(crate) parameters: Vec<Parameter>,
+  pub(crate) return_type: String,
 }
 
 
-#[derive(Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+impl Method {
+  pub(crate) fn new(name: String, description: String, section: String, anchor: String, parameters: Vec<Parameter>, return_type: String) -> Self {
+    Self {
+      name,
+      description,
+      section,
+      anchor,
+      parameters,
+      return_type,
+    }
+  }
+}
+
+
+#[derive(Debug, Clone, Eq, Hash, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
 pub(crate) struct Field {
   pub(crate) name: String,
   pub(crate) r#type: String,
@@ -65,9 +89,43 @@ impl Field {
 }
 
 
+#[derive(Debug, Clone, Eq, Hash, PartialEq, Serialize, Deserialize)]
 pub(crate) struct Parameter {
   pub(crate) name: String,
   pub(crate) r#type: String,
   pub(crate) required: bool,
   pub(crate) description: String,
 }
+
+
+impl Parameter {
+  pub(crate) fn new(name: String, r#type: String, required: bool, description: String) -> Self {
+    Self {
+      name,
+      r#type,
+      required,
+      description,
+    }
+  }
+}
+
+
+#[derive(Debug, Clone, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Version {
+  pub(crate) name: String,
+  pub(crate) date: String,
+  pub(crate) anchor: String,
+  pub(crate) changes: Vec<String>,
+}
+
+
+impl Version {
+  pub(crate) fn new(name: String, date: String, anchor: String, changes: Vec<String>) -> Self {
+    Self {
+      name,
+      date,
+      anchor,
+      changes,
+    }
+  }
+}
@@ -0,0 +1,297 @@
+//!    Rust telegram bot api. The library provides asynchronous access to the telegram bot api.
+//!    Copyright (C) 2024  Andrew Kozmin
+//!
+//!    This program is free software: you can redistribute it and/or modify
+//!    it under the terms of the GNU Affero General Public License as published by
+//!    the Free Software Foundation, either version 3 of the License, or
+//!    (at your option) any later version.
+//!
+//!    This program is distributed in the hope that it will be useful,
+//!    but WITHOUT ANY WARRANTY; without even the implied warranty of
+//!    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//!    GNU Affero General Public License for more details.
+//!
+//!    You should have received a copy of the GNU Affero General Public License
+//!    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+
+use std::future::Future;
+use std::fmt;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Error, Result};
+use reqwest::StatusCode;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+  Closed,
+  Open,
+  HalfOpen,
+}
+
+
+struct Inner {
+  state: CircuitState,
+  consecutive_failures: u32,
+  opened_at: Option<Instant>,
+  trial_started_at: Option<Instant>,
+}
+
+
+pub struct CircuitBreaker {
+  failure_threshold: u32,
+  cool_down: Duration,
+  inner: Mutex<Inner>,
+  on_state_change: Option<Box<dyn Fn(CircuitState) + Send + Sync>>,
+  is_outage: Box<dyn Fn(&Error) -> bool + Send + Sync>,
+}
+
+
+impl CircuitBreaker {
+  pub fn new(failure_threshold: u32, cool_down: Duration) -> Self {
+    Self {
+      failure_threshold,
+      cool_down,
+      inner: Mutex::new(Inner {
+        state: CircuitState::Closed,
+        consecutive_failures: 0,
+        opened_at: None,
+        trial_started_at: None,
+      }),
+      on_state_change: None,
+      is_outage: Box::new(is_outage_error),
+    }
+  }
+
+
+  pub fn on_state_change(mut self, callback: impl Fn(CircuitState) + Send + Sync + 'static) -> Self {
+    self.on_state_change = Some(Box::new(callback));
+    self
+  }
+
+
+  /// Replaces [`is_outage_error`] as the check of which errors of [`call`](Self::call) count as failures.
+  pub fn is_outage(mut self, predicate: impl Fn(&Error) -> bool + Send + Sync + 'static) -> Self {
+    self.is_outage = Box::new(predicate);
+    self
+  }
+
+
+  pub fn state(&self) -> CircuitState {
+    self.lock().state
+  }
+
+
+  /// Runs `request` unless the circuit is open, in which case it fails immediately.
+  ///
+  /// Only errors that signal an outage count as failures. Any other error, like a 400 for a message that was not modified,
+  /// means Telegram answered, so it counts as a success.
+  pub async fn call<T, F: Future<Output = Result<T>>>(&self, request: F) -> Result<T> {
+    if !self.allow_request() {
+      bail!("ERROR: The circuit breaker is open");
+    }
+
+    let result: Result<T> = request.await;
+    match &result {
+      Err(error) if (self.is_outage)(error) => self.record_failure(),
+      _ => self.record_success(),
+    }
+    result
+  }
+
+
+  /// Returns whether a request may be sent now. After the cool-down an open circuit lets one trial request through.
+  /// A trial whose outcome is not recorded within another cool-down, for example because it was cancelled, is replaced by a new one.
+  pub fn allow_request(&self) -> bool {
+    let mut inner: MutexGuard<Inner> = self.lock();
+
+    match inner.state {
+      CircuitState::Closed => true,
+      CircuitState::HalfOpen => {
+        if inner.trial_started_at.is_some_and(|started_at: Instant| started_at.elapsed() >= self.cool_down) {
+          inner.trial_started_at = Some(Instant::now());
+          return true;
+        }
+        false
+      },
+      CircuitState::Open => {
+        if inner.opened_at.is_some_and(|opened_at: Instant| opened_at.elapsed() >= self.cool_down) {
+          inner.trial_started_at = Some(Instant::now());
+          let changed: Option<CircuitState> = set_state(&mut inner, CircuitState::HalfOpen);
+          drop(inner);
+          self.notify(changed);
+          return true;
+        }
+        false
+      },
+    }
+  }
+
+
+  pub fn record_success(&self) {
+    let mut inner: MutexGuard<Inner> = self.lock();
+    inner.consecutive_failures = 0;
+    inner.opened_at = None;
+    inner.trial_started_at = None;
+    let changed: Option<CircuitState> = set_state(&mut inner, CircuitState::Closed);
+    drop(inner);
+    self.notify(changed);
+  }
+
+
+  pub fn record_failure(&self) {
+    let mut inner: MutexGuard<Inner> = self.lock();
+    inner.consecutive_failures = inner.consecutive_failures.saturating_add(1);
+
+    let mut changed: Option<CircuitState> = None;
+    if inner.state == CircuitState::HalfOpen || inner.consecutive_failures >= self.failure_threshold {
+      inner.opened_at = Some(Instant::now());
+      inner.trial_started_at = None;
+      changed = set_state(&mut inner, CircuitState::Open);
+    }
+    drop(inner);
+    self.notify(changed);
+  }
+
+
+  fn lock(&self) -> MutexGuard<'_, Inner> {
+    self.inner.lock().unwrap_or_else(|error| error.into_inner())
+  }
+
+
+  /// Runs the callback outside of the lock, so it can call back into the breaker.
+  fn notify(&self, changed: Option<CircuitState>) {
+    if let (Some(state), Some(callback)) = (changed, &self.on_state_change) {
+      callback(state);
+    }
+  }
+}
+
+
+/// Whether `error` signals an outage: a request that failed to connect or timed out, or a response with a 5xx or 429 status.
+pub fn is_outage_error(error: &Error) -> bool {
+  error.chain()
+    .filter_map(|cause: &(dyn std::error::Error + 'static)| cause.downcast_ref::<reqwest::Error>())
+    .any(|error: &reqwest::Error| match error.status() {
+      Some(status) => status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS,
+      None => error.is_connect() || error.is_timeout() || error.is_request(),
+    })
+}
+
+
+/// Moves the circuit to `state`, returning it if it differs from the current one.
+fn set_state(inner: &mut Inner, state: CircuitState) -> Option<CircuitState> {
+  if inner.state == state {
+    return None;
+  }
+
+  inner.state = state;
+  Some(state)
+}
+
+
+impl Default for CircuitBreaker {
+  fn default() -> Self {
+    Self::new(5, Duration::from_secs(30))
+  }
+}
+
+
+impl fmt::Debug for CircuitBreaker {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("CircuitBreaker")
+      .field("failure_threshold", &self.failure_threshold)
+      .field("cool_down", &self.cool_down)
+      .field("state", &self.state())
+      .finish()
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, OnceLock};
+
+  use anyhow::anyhow;
+
+  use super::*;
+
+
+  #[test]
+  fn lost_trial_is_replaced_after_cool_down() {
+    let breaker: CircuitBreaker = CircuitBreaker::new(1, Duration::from_millis(10));
+    breaker.record_failure();
+    std::thread::sleep(Duration::from_millis(20));
+
+    assert!(breaker.allow_request());
+    assert!(!breaker.allow_request());
+    std::thread::sleep(Duration::from_millis(20));
+    assert!(breaker.allow_request());
+    assert_eq!(breaker.state(), CircuitState::HalfOpen);
+  }
+
+
+  #[test]
+  fn callback_can_read_the_state() {
+    let breaker: Arc<OnceLock<CircuitBreaker>> = Arc::new(OnceLock::new());
+    let observer: Arc<OnceLock<CircuitBreaker>> = breaker.clone();
+    let seen: Arc<Mutex<Vec<CircuitState>>> = Arc::new(Mutex::new(Vec::new()));
+    let record: Arc<Mutex<Vec<CircuitState>>> = seen.clone();
+    breaker.set(CircuitBreaker::new(1, Duration::from_secs(30)).on_state_change(move |_: CircuitState| {
+      if let Some(breaker) = observer.get() {
+        record.lock().unwrap().push(breaker.state());
+      }
+    })).unwrap();
+
+    breaker.get().unwrap().record_failure();
+    breaker.get().unwrap().record_success();
+    assert_eq!(*seen.lock().unwrap(), vec![CircuitState::Open, CircuitState::Closed]);
+  }
+
+
+  #[tokio::test]
+  async fn user_errors_do_not_open_the_circuit() {
+    let breaker: CircuitBreaker = CircuitBreaker::new(1, Duration::from_secs(30));
+    for _ in 0..3 {
+      let result: Result<()> = breaker.call(async { Err(anyhow!("Bad Request: message is not modified")) }).await;
+      assert!(result.is_err());
+    }
+    assert_eq!(breaker.state(), CircuitState::Closed);
+  }
+
+
+  #[tokio::test]
+  async fn transport_errors_open_the_circuit() {
+    let breaker: CircuitBreaker = CircuitBreaker::new(1, Duration::from_secs(30));
+    let result: Result<()> = breaker.call(async {
+      reqwest::get("http://127.0.0.1:9").await?;
+      Ok(())
+    }).await;
+    assert!(result.is_err());
+    assert_eq!(breaker.state(), CircuitState::Open);
+    assert!(breaker.call(async { Ok(()) }).await.is_err());
+  }
+
+
+  #[tokio::test]
+  async fn custom_outage_predicate() {
+    let breaker: CircuitBreaker = CircuitBreaker::new(2, Duration::from_secs(30))
+      .is_outage(|error: &Error| error.to_string().starts_with("Too Many Requests"));
+
+    let _ = breaker.call(async { Err::<(), Error>(anyhow!("Too Many Requests: retry after 5")) }).await;
+    let _ = breaker.call(async { Err::<(), Error>(anyhow!("Forbidden: bot was blocked by the user")) }).await;
+    assert_eq!(breaker.state(), CircuitState::Closed);
+
+    let _ = breaker.call(async { Err::<(), Error>(anyhow!("Too Many Requests: retry after 5")) }).await;
+    let _ = breaker.call(async { Err::<(), Error>(anyhow!("Too Many Requests: retry after 5")) }).await;
+    assert_eq!(breaker.state(), CircuitState::Open);
+  }
+
+
+  #[test]
+  fn other_errors_are_not_outages() {
+    assert!(!is_outage_error(&anyhow!("Bad Request: chat not found")));
+  }
+}
@@ -0,0 +1,238 @@
+//!    Rust telegram bot api. The library provides asynchronous access to the telegram bot api.
+//!    Copyright (C) 2024  Andrew Kozmin
+//!
+//!    This program is free software: you can redistribute it and/or modify
+//!    it under the terms of the GNU Affero General Public License as published by
+//!    the Free Software Foundation, either version 3 of the License, or
+//!    (at your option) any later version.
+//!
+//!    This program is distributed in the hope that it will be useful,
+//!    but WITHOUT ANY WARRANTY; without even the implied warranty of
+//!    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//!    GNU Affero General Public License for more details.
+//!
+//!    You should have received a copy of the GNU Affero General Public License
+//!    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Command<'a> {
+  pub name: &'a str,
+  pub bot_username: Option<&'a str>,
+  pub arguments: &'a str,
+}
+
+
+impl<'a> Command<'a> {
+  /// Splits `/name@bot_username arguments` into its parts, returning `None` if the text is not a command.
+  pub fn parse(text: &'a str) -> Option<Self> {
+    let text: &str = text.strip_prefix('/')?;
+    let (command, arguments): (&str, &str) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+    let (name, bot_username): (&str, Option<&str>) = match command.split_once('@') {
+      Some((name, bot_username)) => (name, Some(bot_username)),
+      None => (command, None),
+    };
+
+    if name.is_empty() {
+      return None;
+    }
+
+    Some(Self {
+      name,
+      bot_username,
+      arguments: arguments.trim(),
+    })
+  }
+
+
+  /// Whether the command is meant for the bot `bot_username`, i.e. it names no bot or names this one.
+  pub fn is_for(&self, bot_username: &str) -> bool {
+    let bot_username: &str = bot_username.trim_start_matches('@');
+    self.bot_username.is_none_or(|name: &str| name.eq_ignore_ascii_case(bot_username))
+  }
+
+
+  pub fn parse_arguments<T: FromArguments>(&self) -> Result<T> {
+    T::from_arguments(&split_arguments(self.arguments)?)
+  }
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Username(pub String);
+
+
+impl FromStr for Username {
+  type Err = anyhow::Error;
+
+  fn from_str(s: &str) -> Result<Self> {
+    let username: &str = s.strip_prefix('@').context("ERROR: A username must start with @")?;
+    let is_valid: bool = (5..=32).contains(&username.len())
+      && username.chars().all(|ch: char| ch.is_ascii_alphanumeric() || ch == '_');
+
+    if !is_valid {
+      bail!("ERROR: Invalid username {s}");
+    }
+
+    Ok(Self(username.to_string()))
+  }
+}
+
+
+pub trait FromArgument: Sized {
+  fn from_argument(argument: &str) -> Result<Self>;
+}
+
+
+macro_rules! from_argument {
+  ($($type:ty),*) => {
+    $(
+      impl FromArgument for $type {
+        fn from_argument(argument: &str) -> Result<Self> {
+          argument.parse().context(format!("ERROR: Invalid {} {argument}", stringify!($type)))
+        }
+      }
+    )*
+  };
+}
+
+
+from_argument!(String, i32, i64, u32, u64, f64, bool, Username);
+
+
+pub trait FromArguments: Sized {
+  fn from_arguments(arguments: &[String]) -> Result<Self>;
+}
+
+
+macro_rules! from_arguments {
+  ($count:literal; $($type:ident),*) => {
+    impl<$($type: FromArgument),*> FromArguments for ($($type,)*) {
+      fn from_arguments(arguments: &[String]) -> Result<Self> {
+        if arguments.len() != $count {
+          bail!("ERROR: Expected {} arguments, got {}", $count, arguments.len());
+        }
+
+        let mut arguments: std::slice::Iter<String> = arguments.iter();
+        Ok(($($type::from_argument(arguments.next().context("ERROR: Missing argument")?)?,)*))
+      }
+    }
+  };
+}
+
+
+from_arguments!(1; A);
+from_arguments!(2; A, B);
+from_arguments!(3; A, B, C);
+from_arguments!(4; A, B, C, D);
+from_arguments!(5; A, B, C, D, E);
+from_arguments!(6; A, B, C, D, E, F);
+
+
+/// Splits command arguments by whitespace, keeping double-quoted strings together.
+pub fn split_arguments(arguments: &str) -> Result<Vec<String>> {
+  let mut result: Vec<String> = Vec::new();
+  let mut current: Option<String> = None;
+  let mut quoted: bool = false;
+  let mut chars: std::str::Chars = arguments.chars();
+
+  while let Some(ch) = chars.next() {
+    match ch {
+      '"' => {
+        quoted = !quoted;
+        current.get_or_insert_with(String::new);
+      },
+      '\\' if quoted => current.get_or_insert_with(String::new).push(chars.next().context("ERROR: Unfinished escape sequence")?),
+      ch if ch.is_whitespace() && !quoted => result.extend(current.take()),
+      ch => current.get_or_insert_with(String::new).push(ch),
+    }
+  }
+
+  if quoted {
+    bail!("ERROR: Unclosed quote in the arguments");
+  }
+
+  result.extend(current);
+  Ok(result)
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+
+  #[test]
+  fn parse_without_bot_username() {
+    let command: Command = Command::parse("/start  deep_link ").unwrap();
+    assert_eq!(command, Command { name: "start", bot_username: None, arguments: "deep_link" });
+    assert!(command.is_for("rtg_test_bot"));
+  }
+
+
+  #[test]
+  fn parse_with_bot_username() {
+    let command: Command = Command::parse("/help@Rtg_Test_Bot").unwrap();
+    assert_eq!(command, Command { name: "help", bot_username: Some("Rtg_Test_Bot"), arguments: "" });
+    assert!(command.is_for("@rtg_test_bot"));
+  }
+
+
+  #[test]
+  fn parse_with_wrong_bot_username() {
+    let command: Command = Command::parse("/help@other_bot").unwrap();
+    assert_eq!(command.bot_username, Some("other_bot"));
+    assert!(!command.is_for("rtg_test_bot"));
+  }
+
+
+  #[test]
+  fn parse_not_a_command() {
+    assert_eq!(Command::parse("hello"), None);
+    assert_eq!(Command::parse("/"), None);
+    assert_eq!(Command::parse("/@rtg_test_bot"), None);
+  }
+
+
+  #[test]
+  fn split_quoted_arguments() {
+    assert_eq!(split_arguments(r#"one "two three"  four"#).unwrap(), ["one", "two three", "four"]);
+    assert_eq!(split_arguments(r#""" next"#).unwrap(), ["", "next"]);
+  }
+
+
+  #[test]
+  fn split_escaped_quotes() {
+    assert_eq!(split_arguments(r#""say \"hi\"" \\"#).unwrap(), [r#"say "hi""#, r"\\"]);
+  }
+
+
+  #[test]
+  fn split_unclosed_quote() {
+    assert!(split_arguments(r#"one "two"#).is_err());
+    assert!(split_arguments(r#""unfinished \"#).is_err());
+  }
+
+
+  #[test]
+  fn tuple_arguments() {
+    let command: Command = Command::parse(r#"/ban @spammer_42 3600 "too many links""#).unwrap();
+    let (username, seconds, reason): (Username, u32, String) = command.parse_arguments().unwrap();
+    assert_eq!(username, Username(String::from("spammer_42")));
+    assert_eq!(seconds, 3600);
+    assert_eq!(reason, "too many links");
+  }
+
+
+  #[test]
+  fn tuple_arguments_count() {
+    assert!(<(i64, i64)>::from_arguments(&[String::from("1")]).is_err());
+    assert!(<(i64, i64)>::from_arguments(&[String::from("1"), String::from("2"), String::from("3")]).is_err());
+    assert!(<(i64,)>::from_arguments(&[String::from("one")]).is_err());
+  }
+}
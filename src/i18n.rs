@@ -0,0 +1,179 @@
+//!    Rust telegram bot api. The library provides asynchronous access to the telegram bot api.
+//!    Copyright (C) 2024  Andrew Kozmin
+//!
+//!    This program is free software: you can redistribute it and/or modify
+//!    it under the terms of the GNU Affero General Public License as published by
+//!    the Free Software Foundation, either version 3 of the License, or
+//!    (at your option) any later version.
+//!
+//!    This program is distributed in the hope that it will be useful,
+//!    but WITHOUT ANY WARRANTY; without even the implied warranty of
+//!    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//!    GNU Affero General Public License for more details.
+//!
+//!    You should have received a copy of the GNU Affero General Public License
+//!    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use fluent_bundle::{concurrent::FluentBundle, FluentError, FluentMessage, FluentResource};
+use unic_langid::LanguageIdentifier;
+
+pub use fluent_bundle::FluentArgs;
+
+
+pub struct Localizer {
+  bundles: HashMap<String, FluentBundle<FluentResource>>,
+  fallback: String,
+}
+
+
+impl Localizer {
+  pub fn new(fallback: &str) -> Self {
+    Self {
+      bundles: HashMap::new(),
+      fallback: normalize(fallback),
+    }
+  }
+
+
+  /// Loads every `<language>.ftl` file from `path`.
+  pub fn load_dir(path: impl AsRef<Path>, fallback: &str) -> Result<Self> {
+    let mut localizer: Self = Self::new(fallback);
+
+    for entry in fs::read_dir(path).context("ERROR: Failed to read the locales directory")? {
+      let path: PathBuf = entry?.path();
+      if path.extension().is_none_or(|extension: &OsStr| extension != "ftl") {
+        continue;
+      }
+
+      let language: &str = path.file_stem().and_then(|stem: &OsStr| stem.to_str()).context("ERROR: Invalid locale file name")?;
+      let source: String = fs::read_to_string(&path).context(format!("ERROR: Failed to read {}", path.display()))?;
+      localizer.add_resource(language, source)?;
+    }
+
+    if !localizer.bundles.contains_key(&localizer.fallback) {
+      bail!("ERROR: There are no messages for the fallback language {}", localizer.fallback);
+    }
+
+    Ok(localizer)
+  }
+
+
+  pub fn add_resource(&mut self, language: &str, source: String) -> Result<()> {
+    let language: String = normalize(language);
+    let resource: FluentResource = match FluentResource::try_new(source) {
+      Ok(resource) => resource,
+      Err((_, errors)) => bail!("ERROR: Failed to parse messages for {language}: {errors:?}"),
+    };
+
+    if !self.bundles.contains_key(&language) {
+      let identifier: LanguageIdentifier = language.parse().context(format!("ERROR: Invalid language {language}"))?;
+      let mut bundle: FluentBundle<FluentResource> = FluentBundle::new_concurrent(vec![identifier]);
+      bundle.set_use_isolating(false);
+      self.bundles.insert(language.clone(), bundle);
+    }
+
+    let bundle: &mut FluentBundle<FluentResource> = self.bundles.get_mut(&language).context("ERROR: Missing bundle")?;
+    if let Err(errors) = bundle.add_resource(resource) {
+      bail!("ERROR: Failed to add messages for {language}: {errors:?}");
+    }
+
+    Ok(())
+  }
+
+
+  /// Picks the language for a user's `language_code`: an exact match, then its base language, then the fallback.
+  pub fn language(&self, language_code: Option<&str>) -> &str {
+    let language: String = match language_code {
+      Some(language_code) => normalize(language_code),
+      None => return &self.fallback,
+    };
+
+    let base: &str = language.split('-').next().unwrap_or_default();
+    for candidate in [language.as_str(), base] {
+      if let Some((language, _)) = self.bundles.get_key_value(candidate) {
+        return language;
+      }
+    }
+
+    &self.fallback
+  }
+
+
+  /// Formats the message `key` for a user's `language_code`, looking it up in the exact language, then its base language,
+  /// then the fallback, and returning the key itself if none of them has it.
+  pub fn t(&self, language_code: Option<&str>, key: &str, args: Option<&FluentArgs>) -> String {
+    let language: String = language_code.map(normalize).unwrap_or_default();
+    let base: &str = language.split('-').next().unwrap_or_default();
+
+    for language in [language.as_str(), base, &self.fallback] {
+      let bundle: &FluentBundle<FluentResource> = match self.bundles.get(language) {
+        Some(bundle) => bundle,
+        None => continue,
+      };
+
+      let message: Option<FluentMessage> = bundle.get_message(key);
+      if let Some(pattern) = message.as_ref().and_then(FluentMessage::value) {
+        let mut errors: Vec<FluentError> = Vec::new();
+        return bundle.format_pattern(pattern, args, &mut errors).into_owned();
+      }
+    }
+
+    key.to_string()
+  }
+}
+
+
+fn normalize(language: &str) -> String {
+  language.trim().replace('_', "-").to_lowercase()
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+
+  fn localizer() -> Localizer {
+    let mut localizer: Localizer = Localizer::new("en");
+    localizer.add_resource("en", String::from("hello = Hello, { $name }!\nbye = Goodbye\nsettings = Settings")).unwrap();
+    localizer.add_resource("pt", String::from("hello = Olá, { $name }!\nbye = Tchau")).unwrap();
+    localizer.add_resource("pt_BR", String::from("bye = Falou")).unwrap();
+    localizer
+  }
+
+
+  #[test]
+  fn language_falls_back_to_base_and_default() {
+    let localizer: Localizer = localizer();
+    assert_eq!(localizer.language(Some("pt-BR")), "pt-br");
+    assert_eq!(localizer.language(Some("pt-PT")), "pt");
+    assert_eq!(localizer.language(Some("de")), "en");
+    assert_eq!(localizer.language(None), "en");
+  }
+
+
+  #[test]
+  fn message_falls_back_to_base_and_default() {
+    let localizer: Localizer = localizer();
+    let mut args: FluentArgs = FluentArgs::new();
+    args.set("name", "Andrew");
+
+    assert_eq!(localizer.t(Some("pt-br"), "bye", None), "Falou");
+    assert_eq!(localizer.t(Some("pt-br"), "hello", Some(&args)), "Olá, Andrew!");
+    assert_eq!(localizer.t(Some("pt-br"), "settings", None), "Settings");
+    assert_eq!(localizer.t(Some("de"), "hello", Some(&args)), "Hello, Andrew!");
+  }
+
+
+  #[test]
+  fn missing_message_returns_the_key() {
+    assert_eq!(localizer().t(Some("pt-br"), "missing", None), "missing");
+  }
+}
@@ -13,3 +13,20 @@
 //!
 //!    You should have received a copy of the GNU Affero General Public License
 //!    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+
+pub mod backoff;
+pub mod circuit_breaker;
+pub mod command;
+#[cfg(feature = "i18n")]
+pub mod i18n;
+pub mod limits;
+pub mod login;
+#[cfg(feature = "passport")]
+pub mod passport;
+pub mod text;
+pub mod token;
+pub mod webapps;
+pub mod webhook;
+
+mod utils;
@@ -0,0 +1,53 @@
+//!    Rust telegram bot api. The library provides asynchronous access to the telegram bot api.
+//!    Copyright (C) 2024  Andrew Kozmin
+//!
+//!    This program is free software: you can redistribute it and/or modify
+//!    it under the terms of the GNU Affero General Public License as published by
+//!    the Free Software Foundation, either version 3 of the License, or
+//!    (at your option) any later version.
+//!
+//!    This program is distributed in the hope that it will be useful,
+//!    but WITHOUT ANY WARRANTY; without even the implied warranty of
+//!    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//!    GNU Affero General Public License for more details.
+//!
+//!    You should have received a copy of the GNU Affero General Public License
+//!    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+
+pub const MAX_MESSAGE_LENGTH: usize = 4096;
+pub const MAX_CAPTION_LENGTH: usize = 1024;
+pub const MAX_CALLBACK_DATA_SIZE: usize = 64;
+pub const MAX_START_PARAMETER_LENGTH: usize = 64;
+
+pub const MIN_MEDIA_GROUP_SIZE: usize = 2;
+pub const MAX_MEDIA_GROUP_SIZE: usize = 10;
+
+pub const MAX_POLL_QUESTION_LENGTH: usize = 300;
+pub const MAX_POLL_OPTION_LENGTH: usize = 100;
+pub const MIN_POLL_OPTIONS: usize = 2;
+pub const MAX_POLL_OPTIONS: usize = 10;
+pub const MAX_POLL_EXPLANATION_LENGTH: usize = 200;
+pub const MIN_POLL_OPEN_PERIOD: u32 = 5;
+pub const MAX_POLL_OPEN_PERIOD: u32 = 600;
+
+pub const MAX_COMMANDS: usize = 100;
+pub const MAX_COMMAND_LENGTH: usize = 32;
+pub const MAX_COMMAND_DESCRIPTION_LENGTH: usize = 256;
+
+pub const MAX_BOT_NAME_LENGTH: usize = 64;
+pub const MAX_BOT_DESCRIPTION_LENGTH: usize = 512;
+pub const MAX_BOT_SHORT_DESCRIPTION_LENGTH: usize = 120;
+
+pub const MAX_INLINE_QUERY_RESULTS: usize = 50;
+pub const MAX_UPDATES_LIMIT: usize = 100;
+pub const MAX_WEBHOOK_CONNECTIONS: usize = 100;
+pub const MAX_WEBHOOK_SECRET_TOKEN_LENGTH: usize = 256;
+
+pub const MIN_LIVE_PERIOD: u32 = 60;
+pub const MAX_LIVE_PERIOD: u32 = 86400;
+
+pub const MAX_UPLOAD_FILE_SIZE: u64 = 50 * 1024 * 1024;
+pub const MAX_UPLOAD_PHOTO_SIZE: u64 = 10 * 1024 * 1024;
+pub const MAX_DOWNLOAD_FILE_SIZE: u64 = 20 * 1024 * 1024;
+pub const MAX_LOCAL_SERVER_FILE_SIZE: u64 = 2000 * 1024 * 1024;
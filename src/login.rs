@@ -0,0 +1,143 @@
+//!    Rust telegram bot api. The library provides asynchronous access to the telegram bot api.
+//!    Copyright (C) 2024  Andrew Kozmin
+//!
+//!    This program is free software: you can redistribute it and/or modify
+//!    it under the terms of the GNU Affero General Public License as published by
+//!    the Free Software Foundation, either version 3 of the License, or
+//!    (at your option) any later version.
+//!
+//!    This program is distributed in the hope that it will be useful,
+//!    but WITHOUT ANY WARRANTY; without even the implied warranty of
+//!    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//!    GNU Affero General Public License for more details.
+//!
+//!    You should have received a copy of the GNU Affero General Public License
+//!    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use ring::{digest, hmac};
+use serde::Deserialize;
+
+use crate::utils::{check_auth_date, data_check_string, decode_hex};
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct LoginData {
+  pub id: i64,
+  pub first_name: String,
+  pub last_name: Option<String>,
+  pub username: Option<String>,
+  pub photo_url: Option<String>,
+  pub auth_date: i64,
+  pub hash: String,
+}
+
+
+impl LoginData {
+  /// Checks the Login Widget hash against the bot token and rejects data older than `max_age`.
+  pub fn verify(&self, bot_token: &str, max_age: Duration) -> Result<()> {
+    let mut fields: BTreeMap<String, String> = BTreeMap::from([
+      (String::from("id"), self.id.to_string()),
+      (String::from("first_name"), self.first_name.clone()),
+      (String::from("auth_date"), self.auth_date.to_string()),
+    ]);
+
+    let optional_fields: [(&str, &Option<String>); 3] = [
+      ("last_name", &self.last_name),
+      ("username", &self.username),
+      ("photo_url", &self.photo_url),
+    ];
+
+    for (name, value) in optional_fields {
+      if let Some(value) = value {
+        fields.insert(name.to_string(), value.clone());
+      }
+    }
+
+    let secret_key: digest::Digest = digest::digest(&digest::SHA256, bot_token.as_bytes());
+    let key: hmac::Key = hmac::Key::new(hmac::HMAC_SHA256, secret_key.as_ref());
+    let signature: Vec<u8> = decode_hex(&self.hash)?;
+
+    if hmac::verify(&key, data_check_string(&fields).as_bytes(), &signature).is_err() {
+      bail!("ERROR: The login data hash is invalid");
+    }
+
+    check_auth_date(self.auth_date, max_age)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+
+  const BOT_TOKEN: &str = "123456:ABC-DEF1234ghIkl-zyx57W2v1u123ew11";
+
+  const NO_MAX_AGE: Duration = Duration::MAX;
+
+
+  fn login_data() -> LoginData {
+    LoginData {
+      id: 42,
+      first_name: String::from("Andrew"),
+      last_name: None,
+      username: Some(String::from("andrew")),
+      photo_url: None,
+      auth_date: 1700000000,
+      hash: String::from("3542cf4a3bbc9354a8646798cb250ec9bd9aa482861dc8581f0b5d052a5b6a28"),
+    }
+  }
+
+
+  #[test]
+  fn valid_login_data() {
+    assert!(login_data().verify(BOT_TOKEN, NO_MAX_AGE).is_ok());
+  }
+
+
+  #[test]
+  fn wrong_bot_token() {
+    assert!(login_data().verify("654321:ABC-DEF1234ghIkl-zyx57W2v1u123ew11", NO_MAX_AGE).is_err());
+  }
+
+
+  #[test]
+  fn tampered_field() {
+    let mut login_data: LoginData = login_data();
+    login_data.id = 43;
+    assert!(login_data.verify(BOT_TOKEN, NO_MAX_AGE).is_err());
+
+    let mut login_data: LoginData = self::login_data();
+    login_data.last_name = Some(String::from("Kozmin"));
+    assert!(login_data.verify(BOT_TOKEN, NO_MAX_AGE).is_err());
+  }
+
+
+  #[test]
+  fn tampered_hash() {
+    let mut login_data: LoginData = login_data();
+    login_data.hash = login_data.hash.replace("3542", "3543");
+    assert!(login_data.verify(BOT_TOKEN, NO_MAX_AGE).is_err());
+  }
+
+
+  #[test]
+  fn malformed_hash() {
+    for hash in ["", "354", "zz42", "+542"] {
+      let mut login_data: LoginData = login_data();
+      login_data.hash = String::from(hash);
+      assert!(login_data.verify(BOT_TOKEN, NO_MAX_AGE).is_err(), "{hash}");
+    }
+  }
+
+
+  #[test]
+  fn outdated_login_data() {
+    assert!(login_data().verify(BOT_TOKEN, Duration::from_secs(60)).is_err());
+  }
+}
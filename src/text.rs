@@ -0,0 +1,500 @@
+//!    Rust telegram bot api. The library provides asynchronous access to the telegram bot api.
+//!    Copyright (C) 2024  Andrew Kozmin
+//!
+//!    This program is free software: you can redistribute it and/or modify
+//!    it under the terms of the GNU Affero General Public License as published by
+//!    the Free Software Foundation, either version 3 of the License, or
+//!    (at your option) any later version.
+//!
+//!    This program is distributed in the hope that it will be useful,
+//!    but WITHOUT ANY WARRANTY; without even the implied warranty of
+//!    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//!    GNU Affero General Public License for more details.
+//!
+//!    You should have received a copy of the GNU Affero General Public License
+//!    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+
+use std::ops::Range;
+
+use anyhow::{bail, Result};
+
+use crate::limits::MAX_MESSAGE_LENGTH;
+
+
+/// Splits a message text into parts that fit into [`MAX_MESSAGE_LENGTH`].
+pub fn split_message(text: &str) -> Vec<&str> {
+  split_text(text, MAX_MESSAGE_LENGTH)
+}
+
+
+/// Splits a formatted message text into parts that fit into [`MAX_MESSAGE_LENGTH`], see [`split_formatted`].
+pub fn split_formatted_message(text: &str, markup: Markup) -> Vec<String> {
+  split_formatted(text, markup, MAX_MESSAGE_LENGTH)
+}
+
+
+/// Fails if `text` is longer than `max_length` UTF-16 code units, e.g. a caption over [`crate::limits::MAX_CAPTION_LENGTH`].
+pub fn check_length(text: &str, max_length: usize) -> Result<()> {
+  let length: usize = utf16_len(text);
+  if length > max_length {
+    bail!("ERROR: The text is {length} characters long, but at most {max_length} are allowed");
+  }
+
+  Ok(())
+}
+
+
+/// Splits `text` into parts of at most `max_length` UTF-16 code units, the unit Telegram counts message length in.
+///
+/// Parts are cut at paragraph breaks, then line breaks, then whitespace, and never inside a ``` code block
+/// unless the block itself does not fit into one part. Use [`split_formatted`] for text with a `parse_mode`.
+pub fn split_text(text: &str, max_length: usize) -> Vec<&str> {
+  let code_blocks: Vec<Range<usize>> = find_code_blocks(text);
+  let mut result: Vec<&str> = Vec::new();
+  let mut start: usize = 0;
+
+  while utf16_len(&text[start..]) > max_length {
+    let limit: usize = start + byte_index_of_utf16_offset(&text[start..], max_length);
+    let is_safe = |idx: &usize| -> bool { *idx > start && !code_blocks.iter().any(|block: &Range<usize>| block.contains(idx)) };
+
+    let cut: usize = find_last(text, start, limit, "\n\n", &is_safe)
+      .or_else(|| find_last(text, start, limit, "\n", &is_safe))
+      .or_else(|| find_last_whitespace(text, start, limit, &is_safe))
+      .unwrap_or(limit.max(start + text[start..].chars().next().map_or(0, char::len_utf8)));
+
+    let part: &str = text[start..cut].trim_end();
+    if !part.is_empty() {
+      result.push(part);
+    }
+
+    start = cut + (text[cut..].len() - text[cut..].trim_start().len());
+  }
+
+  let part: &str = text[start..].trim_end();
+  if !part.is_empty() {
+    result.push(part);
+  }
+
+  result
+}
+
+
+/// The markup a formatted text is written in, as set by its `parse_mode`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Markup {
+  Html,
+  MarkdownV2,
+}
+
+
+/// Splits `text` written in `markup` into parts of at most `max_length` UTF-16 code units of visible text,
+/// the length Telegram checks after parsing the entities.
+///
+/// Parts are cut at the same places as in [`split_text`], but never inside a tag, an escape sequence or a link target,
+/// and not inside a code block unless it does not fit into one part. Entities that are open at a cut are closed
+/// at the end of the part and reopened at the start of the next one.
+pub fn split_formatted(text: &str, markup: Markup, max_length: usize) -> Vec<String> {
+  let tokens: Vec<Token> = match markup {
+    Markup::Html => tokenize_html(text),
+    Markup::MarkdownV2 => tokenize_markdown(text),
+  };
+  let mut result: Vec<String> = Vec::new();
+  let mut stack: Vec<Entity> = Vec::new();
+  let mut start: usize = 0;
+
+  while start < tokens.len() {
+    let opening: String = stack.iter().map(|entity: &Entity| entity.open.as_str()).collect();
+    let mut part: Vec<&str> = vec![&opening];
+    let mut visible: bool = false;
+    let cut: usize = find_cut(&tokens, start, &stack, max_length);
+
+    for token in &tokens[start..cut] {
+      visible |= matches!(token.kind, Kind::Text(ch) if !ch.is_whitespace());
+      part.push(&text[token.range.clone()]);
+      apply(&mut stack, token);
+    }
+
+    let mut end: usize = cut;
+    if cut < tokens.len() {
+      while let Some(token) = tokens.get(end).filter(|token: &&Token| matches!(token.kind, Kind::Close(_)) || token.is_whitespace()) {
+        if matches!(token.kind, Kind::Close(_)) {
+          trim_whitespace(&mut part);
+          part.push(&text[token.range.clone()]);
+        }
+        apply(&mut stack, token);
+        end += 1;
+      }
+    }
+
+    trim_whitespace(&mut part);
+    if end < tokens.len() {
+      part.extend(stack.iter().rev().map(|entity: &Entity| entity.close.as_str()));
+    }
+    if visible {
+      result.push(part.concat());
+    }
+
+    start = end;
+  }
+
+  result
+}
+
+
+#[derive(Clone)]
+struct Entity {
+  key: String,
+  open: String,
+  close: String,
+  code: bool,
+  /// Ends with the line, like a MarkdownV2 block quote.
+  line: bool,
+}
+
+
+struct Token {
+  range: Range<usize>,
+  kind: Kind,
+}
+
+
+enum Kind {
+  /// A visible character, which may be written as an escape sequence.
+  Text(char),
+  Open(Entity),
+  Close(String),
+}
+
+
+impl Token {
+  fn is_whitespace(&self) -> bool {
+    matches!(self.kind, Kind::Text(ch) if ch.is_whitespace())
+  }
+
+
+  fn is_newline(&self) -> bool {
+    matches!(self.kind, Kind::Text('\n'))
+  }
+
+
+  fn width(&self) -> usize {
+    match self.kind {
+      Kind::Text(ch) => ch.len_utf16(),
+      _ => 0,
+    }
+  }
+}
+
+
+fn apply(stack: &mut Vec<Entity>, token: &Token) {
+  match &token.kind {
+    Kind::Open(entity) => stack.push(entity.clone()),
+    Kind::Close(key) => {
+      if let Some(idx) = stack.iter().rposition(|entity: &Entity| entity.key == *key) {
+        stack.remove(idx);
+      }
+    },
+    Kind::Text('\n') => stack.retain(|entity: &Entity| !entity.line),
+    Kind::Text(_) => (),
+  }
+}
+
+
+/// Finds the token to start the next part at, or the end if the rest fits into one part.
+fn find_cut(tokens: &[Token], start: usize, stack: &[Entity], max_length: usize) -> usize {
+  let mut stack: Vec<Entity> = stack.to_vec();
+  let mut length: usize = 0;
+  let mut paragraph: Option<usize> = None;
+  let mut line: Option<usize> = None;
+  let mut whitespace: Option<usize> = None;
+
+  for (idx, token) in tokens.iter().enumerate().skip(start) {
+    if idx > start && !stack.iter().any(|entity: &Entity| entity.code) {
+      if token.is_newline() && tokens.get(idx + 1).is_some_and(Token::is_newline) {
+        paragraph = Some(idx);
+      }
+      if token.is_newline() {
+        line = Some(idx);
+      }
+      if token.is_whitespace() {
+        whitespace = Some(idx);
+      }
+    }
+
+    length += token.width();
+    if length > max_length {
+      return paragraph.or(line).or(whitespace).unwrap_or(idx.max(start + 1));
+    }
+    apply(&mut stack, token);
+  }
+
+  tokens.len()
+}
+
+
+/// Drops the whitespace at the end of a part, keeping the markup after it.
+fn trim_whitespace(part: &mut Vec<&str>) {
+  while part.last().is_some_and(|piece: &&str| piece.chars().all(char::is_whitespace) && !piece.is_empty()) {
+    part.pop();
+  }
+}
+
+
+fn tokenize_html(text: &str) -> Vec<Token> {
+  let mut tokens: Vec<Token> = Vec::new();
+  let mut idx: usize = 0;
+
+  while idx < text.len() {
+    let rest: &str = &text[idx..];
+    let tag: Option<&str> = rest.strip_prefix('<').and_then(|tag: &str| tag.find('>').map(|end: usize| &tag[..end]));
+    let reference: Option<(&str, char)> = rest.strip_prefix('&')
+      .and_then(|reference: &str| reference.find(';').map(|end: usize| &reference[..end]))
+      .and_then(|name: &str| decode_reference(name).map(|ch: char| (name, ch)));
+
+    let (length, kind): (usize, Kind) = if let Some(tag) = tag {
+      let name: String = tag.trim_start_matches('/').split(|ch: char| ch.is_whitespace()).next().unwrap_or_default().to_lowercase();
+      if tag.starts_with('/') {
+        (tag.len() + 2, Kind::Close(name))
+      } else {
+        let code: bool = name == "pre" || name == "code";
+        let close: String = format!("</{name}>");
+        (tag.len() + 2, Kind::Open(Entity { key: name, open: format!("<{tag}>"), close, code, line: false }))
+      }
+    } else if let Some((name, ch)) = reference {
+      (name.len() + 2, Kind::Text(ch))
+    } else {
+      let ch: char = rest.chars().next().unwrap_or_default();
+      (ch.len_utf8(), Kind::Text(ch))
+    };
+
+    tokens.push(Token { range: idx..idx + length, kind });
+    idx += length;
+  }
+
+  tokens
+}
+
+
+fn decode_reference(name: &str) -> Option<char> {
+  match name {
+    "lt" => Some('<'),
+    "gt" => Some('>'),
+    "amp" => Some('&'),
+    "quot" => Some('"'),
+    _ => {
+      let code: u32 = match name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+        None => name.strip_prefix('#')?.parse().ok()?,
+      };
+      char::from_u32(code)
+    },
+  }
+}
+
+
+const MARKDOWN_MARKERS: [&str; 6] = ["||", "__", "*", "_", "~", "`"];
+
+
+fn tokenize_markdown(text: &str) -> Vec<Token> {
+  let mut tokens: Vec<Token> = Vec::new();
+  let mut stack: Vec<Entity> = Vec::new();
+  let mut idx: usize = 0;
+
+  while idx < text.len() {
+    let rest: &str = &text[idx..];
+    let ch: char = rest.chars().next().unwrap_or_default();
+    let code: Option<&str> = stack.iter().find(|entity: &&Entity| entity.code).map(|entity: &Entity| entity.key.as_str());
+    let line_start: bool = tokens.last().is_none_or(Token::is_newline);
+    let marker: Option<&str> = MARKDOWN_MARKERS.into_iter().find(|marker: &&str| rest.starts_with(marker));
+
+    let (length, kind): (usize, Kind) = if let Some(escaped) = rest.strip_prefix('\\').and_then(|escaped: &str| escaped.chars().next()) {
+      (1 + escaped.len_utf8(), Kind::Text(escaped))
+    } else if let Some(code) = code {
+      if rest.starts_with(code) {
+        (code.len(), Kind::Close(code.to_string()))
+      } else {
+        (ch.len_utf8(), Kind::Text(ch))
+      }
+    } else if line_start && ch == '>' {
+      (1, Kind::Open(Entity { key: String::from(">"), open: String::from(">"), close: String::new(), code: false, line: true }))
+    } else if let Some(language) = rest.strip_prefix("```") {
+      let open: &str = match language.find('\n') {
+        Some(end) if !language[..end].contains('`') => &rest[..end + 4],
+        _ => "```",
+      };
+      (open.len(), Kind::Open(Entity { key: String::from("```"), open: open.to_string(), close: String::from("```"), code: true, line: false }))
+    } else if let Some(marker) = marker {
+      if stack.iter().any(|entity: &Entity| entity.key == marker) {
+        (marker.len(), Kind::Close(marker.to_string()))
+      } else {
+        let entity: Entity = Entity { key: marker.to_string(), open: marker.to_string(), close: marker.to_string(), code: marker == "`", line: false };
+        (marker.len(), Kind::Open(entity))
+      }
+    } else if let Some(target) = (ch == ']' && stack.iter().any(|entity: &Entity| entity.key == "[")).then(|| link_target(rest)).flatten() {
+      (target.len(), Kind::Close(String::from("[")))
+    } else if let Some((open, target)) = link(rest) {
+      (open.len(), Kind::Open(Entity { key: String::from("["), open: open.to_string(), close: target.to_string(), code: false, line: false }))
+    } else {
+      (ch.len_utf8(), Kind::Text(ch))
+    };
+
+    let token: Token = Token { range: idx..idx + length, kind };
+    apply(&mut stack, &token);
+    tokens.push(token);
+    idx += length;
+  }
+
+  tokens
+}
+
+
+/// Matches the start of a `[text](url)` link or a `![emoji](url)` custom emoji, returning the opening bracket and the `](url)` that closes it.
+fn link(text: &str) -> Option<(&str, &str)> {
+  let open: &str = ["![", "["].into_iter().find(|open: &&str| text.starts_with(open))?;
+  let mut escaped: bool = false;
+
+  for (idx, ch) in text.char_indices().skip(open.len()) {
+    match ch {
+      _ if escaped => escaped = false,
+      '\\' => escaped = true,
+      '[' => return None,
+      ']' => return link_target(&text[idx..]).map(|target: &str| (open, target)),
+      _ => (),
+    }
+  }
+
+  None
+}
+
+
+/// Matches the `](url)` at the start of `text`.
+fn link_target(text: &str) -> Option<&str> {
+  let url: &str = text.strip_prefix("](")?;
+  let mut escaped: bool = false;
+
+  for (idx, ch) in url.char_indices() {
+    match ch {
+      _ if escaped => escaped = false,
+      '\\' => escaped = true,
+      ')' => return Some(&text[..idx + 3]),
+      _ => (),
+    }
+  }
+
+  None
+}
+
+
+fn find_code_blocks(text: &str) -> Vec<Range<usize>> {
+  let fences: Vec<usize> = text.match_indices("```").map(|(idx, _)| idx).collect();
+
+  fences.chunks(2)
+    .filter_map(|pair: &[usize]| match pair {
+      [open, close] => Some(open + 1..close + 3),
+      _ => None,
+    })
+    .collect()
+}
+
+
+fn find_last(text: &str, start: usize, limit: usize, separator: &str, is_safe: &impl Fn(&usize) -> bool) -> Option<usize> {
+  text[start..limit].rmatch_indices(separator)
+    .map(|(idx, _)| start + idx)
+    .find(is_safe)
+}
+
+
+fn find_last_whitespace(text: &str, start: usize, limit: usize, is_safe: &impl Fn(&usize) -> bool) -> Option<usize> {
+  text[start..limit].char_indices()
+    .rev()
+    .filter(|(_, ch)| ch.is_whitespace())
+    .map(|(idx, _)| start + idx)
+    .find(is_safe)
+}
+
+
+fn utf16_len(text: &str) -> usize {
+  text.chars().map(char::len_utf16).sum()
+}
+
+
+fn byte_index_of_utf16_offset(text: &str, offset: usize) -> usize {
+  let mut utf16_idx: usize = 0;
+
+  for (idx, ch) in text.char_indices() {
+    utf16_idx += ch.len_utf16();
+    if utf16_idx > offset {
+      return idx;
+    }
+  }
+
+  text.len()
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+
+  #[test]
+  fn html_entities_are_reopened() {
+    assert_eq!(split_formatted("<b>aaaa bbbb</b>", Markup::Html, 6), ["<b>aaaa</b>", "<b>bbbb</b>"]);
+    assert_eq!(
+      split_formatted("<a href=\"https://example.com\">first second</a>", Markup::Html, 6),
+      ["<a href=\"https://example.com\">first</a>", "<a href=\"https://example.com\">second</a>"],
+    );
+  }
+
+
+  #[test]
+  fn html_markup_is_not_counted() {
+    assert_eq!(split_formatted("<b>bold <i>both</i></b> plain text", Markup::Html, 9), ["<b>bold <i>both</i></b>", "plain", "text"]);
+    assert_eq!(split_formatted("&lt;&lt;&lt; &gt;", Markup::Html, 3), ["&lt;&lt;&lt;", "&gt;"]);
+  }
+
+
+  #[test]
+  fn html_code_block_is_cut_only_if_too_long() {
+    assert_eq!(
+      split_formatted("<pre><code class=\"language-rust\">fn a() {}\nfn b() {}</code></pre>", Markup::Html, 10),
+      ["<pre><code class=\"language-rust\">fn a() {}</code></pre>", "<pre><code class=\"language-rust\">fn b() {}</code></pre>"],
+    );
+    assert_eq!(split_formatted("text <code>co de</code> end", Markup::Html, 7), ["text", "<code>co de</code>", "end"]);
+  }
+
+
+  #[test]
+  fn markdown_entities_are_reopened() {
+    assert_eq!(split_formatted("*bold text* and _more_", Markup::MarkdownV2, 9), ["*bold text*", "and _more_"]);
+    assert_eq!(split_formatted("||aaa __bbb__||", Markup::MarkdownV2, 3), ["||aaa||", "||__bbb__||"]);
+    assert_eq!(
+      split_formatted("[first second](https://example.com/a\\)b)", Markup::MarkdownV2, 6),
+      ["[first](https://example.com/a\\)b)", "[second](https://example.com/a\\)b)"],
+    );
+    assert_eq!(
+      split_formatted("```rust\nfn a() {}\nfn b() {}\n```", Markup::MarkdownV2, 10),
+      ["```rust\nfn a() {}```", "```rust\nfn b() {}\n```"],
+    );
+  }
+
+
+  #[test]
+  fn markdown_escapes_are_not_cut() {
+    assert_eq!(split_formatted("a\\*b \\*c", Markup::MarkdownV2, 3), ["a\\*b", "\\*c"]);
+  }
+
+
+  #[test]
+  fn markdown_block_quote_ends_with_the_line() {
+    assert_eq!(split_formatted(">one two\n>three", Markup::MarkdownV2, 4), [">one", ">two", ">thre", ">e"]);
+  }
+
+
+  #[test]
+  fn cuts_at_paragraphs_first() {
+    assert_eq!(split_formatted("para one\n\npara two *x y*", Markup::MarkdownV2, 14), ["para one", "para two *x y*"]);
+    assert_eq!(split_formatted("short", Markup::MarkdownV2, 14), ["short"]);
+  }
+}
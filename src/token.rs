@@ -0,0 +1,143 @@
+//!    Rust telegram bot api. The library provides asynchronous access to the telegram bot api.
+//!    Copyright (C) 2024  Andrew Kozmin
+//!
+//!    This program is free software: you can redistribute it and/or modify
+//!    it under the terms of the GNU Affero General Public License as published by
+//!    the Free Software Foundation, either version 3 of the License, or
+//!    (at your option) any later version.
+//!
+//!    This program is distributed in the hope that it will be useful,
+//!    but WITHOUT ANY WARRANTY; without even the implied warranty of
+//!    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//!    GNU Affero General Public License for more details.
+//!
+//!    You should have received a copy of the GNU Affero General Public License
+//!    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Error, Result};
+use zeroize::Zeroize;
+
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct BotToken {
+  token: String,
+  bot_id: u64,
+}
+
+
+impl BotToken {
+  /// Validates the `<bot_id>:<hash>` format of the token.
+  pub fn new(token: String) -> Result<Self> {
+    let (bot_id, hash): (&str, &str) = token.split_once(':').context("ERROR: The bot token must have the '<bot_id>:<hash>' format")?;
+
+    if hash.is_empty() || !hash.chars().all(|ch: char| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-') {
+      bail!("ERROR: The bot token hash is invalid");
+    }
+
+    let bot_id: u64 = bot_id.parse().context("ERROR: The bot token id is not a number")?;
+
+    Ok(Self {
+      token,
+      bot_id,
+    })
+  }
+
+
+  /// Reads the token from the `TELEGRAM_BOT_TOKEN` environment variable.
+  pub fn from_env() -> Result<Self> {
+    let token: String = env::var("TELEGRAM_BOT_TOKEN").context("ERROR: The TELEGRAM_BOT_TOKEN environment variable is not set")?;
+    Self::new(token)
+  }
+
+
+  pub fn bot_id(&self) -> u64 {
+    self.bot_id
+  }
+
+
+  pub fn expose_secret(&self) -> &str {
+    &self.token
+  }
+
+
+  /// Replaces every occurrence of the token in `text`, e.g. in a request URL before it is logged.
+  pub fn redact(&self, text: &str) -> String {
+    text.replace(&self.token, &self.to_string())
+  }
+}
+
+
+impl FromStr for BotToken {
+  type Err = Error;
+
+  fn from_str(token: &str) -> Result<Self> {
+    Self::new(token.to_string())
+  }
+}
+
+
+impl fmt::Debug for BotToken {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "BotToken({}:[REDACTED])", self.bot_id)
+  }
+}
+
+
+impl fmt::Display for BotToken {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}:[REDACTED]", self.bot_id)
+  }
+}
+
+
+impl Drop for BotToken {
+  fn drop(&mut self) {
+    self.token.zeroize();
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+
+  const TOKEN: &str = "123456:ABC-DEF1234ghIkl-zyx57W2v1u123ew11";
+
+
+  #[test]
+  fn valid_token() {
+    let token: BotToken = TOKEN.parse().unwrap();
+    assert_eq!(token.bot_id(), 123456);
+    assert_eq!(token.expose_secret(), TOKEN);
+  }
+
+
+  #[test]
+  fn malformed_token() {
+    for token in ["", "123456", "123456:", ":ABC-DEF1234", "bot:ABC-DEF1234", "-1:ABC-DEF1234", "123456:ABC DEF", "123456:ABC:DEF", "123456:ABC/DEF"] {
+      assert!(token.parse::<BotToken>().is_err(), "{token}");
+    }
+  }
+
+
+  #[test]
+  fn debug_and_display_are_redacted() {
+    let token: BotToken = TOKEN.parse().unwrap();
+    assert_eq!(format!("{token:?}"), "BotToken(123456:[REDACTED])");
+    assert_eq!(token.to_string(), "123456:[REDACTED]");
+  }
+
+
+  #[test]
+  fn redacts_request_urls() {
+    let token: BotToken = TOKEN.parse().unwrap();
+    let url: String = format!("https://api.telegram.org/bot{TOKEN}/getMe");
+    assert_eq!(token.redact(&url), "https://api.telegram.org/bot123456:[REDACTED]/getMe");
+  }
+}
@@ -0,0 +1,127 @@
+//!    Rust telegram bot api. The library provides asynchronous access to the telegram bot api.
+//!    Copyright (C) 2024  Andrew Kozmin
+//!
+//!    This program is free software: you can redistribute it and/or modify
+//!    it under the terms of the GNU Affero General Public License as published by
+//!    the Free Software Foundation, either version 3 of the License, or
+//!    (at your option) any later version.
+//!
+//!    This program is distributed in the hope that it will be useful,
+//!    but WITHOUT ANY WARRANTY; without even the implied warranty of
+//!    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//!    GNU Affero General Public License for more details.
+//!
+//!    You should have received a copy of the GNU Affero General Public License
+//!    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Context, Result};
+
+
+/// How far ahead of the local clock an auth date may be, to tolerate clock drift.
+const MAX_CLOCK_SKEW: Duration = Duration::from_secs(60);
+
+
+pub(crate) fn data_check_string(fields: &BTreeMap<String, String>) -> String {
+  fields.iter()
+    .map(|(key, value)| format!("{key}={value}"))
+    .collect::<Vec<String>>()
+    .join("\n")
+}
+
+
+pub(crate) fn check_auth_date(auth_date: i64, max_age: Duration) -> Result<()> {
+  let now: u64 = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+  let auth_date: u64 = u64::try_from(auth_date).context("ERROR: The auth date is negative")?;
+
+  if auth_date > now.saturating_add(MAX_CLOCK_SKEW.as_secs()) {
+    bail!("ERROR: The auth date is in the future");
+  }
+  if now.saturating_sub(auth_date) > max_age.as_secs() {
+    bail!("ERROR: The auth data is outdated");
+  }
+
+  Ok(())
+}
+
+
+pub(crate) fn decode_hex(value: &str) -> Result<Vec<u8>> {
+  if !value.len().is_multiple_of(2) {
+    bail!("ERROR: The hash has an odd length");
+  }
+  // from_str_radix also accepts a sign, so "+f" would otherwise decode.
+  if !value.bytes().all(|byte: u8| byte.is_ascii_hexdigit()) {
+    bail!("ERROR: The hash is not a hex string");
+  }
+
+  (0..value.len())
+    .step_by(2)
+    .map(|idx: usize| u8::from_str_radix(value.get(idx..idx + 2).context("ERROR: The hash is not a hex string")?, 16).context("ERROR: The hash is not a hex string"))
+    .collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+
+  fn now() -> i64 {
+    i64::try_from(SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()).unwrap()
+  }
+
+
+  #[test]
+  fn data_check_string_is_sorted() {
+    let fields: BTreeMap<String, String> = BTreeMap::from([
+      (String::from("user"), String::from("{}")),
+      (String::from("auth_date"), String::from("1")),
+      (String::from("query_id"), String::from("a")),
+    ]);
+    assert_eq!(data_check_string(&fields), "auth_date=1\nquery_id=a\nuser={}");
+  }
+
+
+  #[test]
+  fn fresh_auth_date() {
+    assert!(check_auth_date(now() - 10, Duration::from_secs(60)).is_ok());
+    assert!(check_auth_date(now() + 10, Duration::from_secs(60)).is_ok());
+  }
+
+
+  #[test]
+  fn outdated_auth_date() {
+    assert!(check_auth_date(now() - 120, Duration::from_secs(60)).is_err());
+  }
+
+
+  #[test]
+  fn future_auth_date() {
+    assert!(check_auth_date(now() + 3600, Duration::from_secs(60)).is_err());
+    assert!(check_auth_date(i64::MAX, Duration::MAX).is_err());
+  }
+
+
+  #[test]
+  fn negative_auth_date() {
+    assert!(check_auth_date(-1, Duration::MAX).is_err());
+  }
+
+
+  #[test]
+  fn valid_hex() {
+    assert_eq!(decode_hex("00ff7Fa0").unwrap(), vec![0x00, 0xff, 0x7f, 0xa0]);
+    assert_eq!(decode_hex("").unwrap(), Vec::<u8>::new());
+  }
+
+
+  #[test]
+  fn malformed_hex() {
+    for value in ["0", "0g", "+f", "-f", " f", "ff\u{e9}"] {
+      assert!(decode_hex(value).is_err(), "{value}");
+    }
+  }
+}
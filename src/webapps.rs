@@ -0,0 +1,181 @@
+//!    Rust telegram bot api. The library provides asynchronous access to the telegram bot api.
+//!    Copyright (C) 2024  Andrew Kozmin
+//!
+//!    This program is free software: you can redistribute it and/or modify
+//!    it under the terms of the GNU Affero General Public License as published by
+//!    the Free Software Foundation, either version 3 of the License, or
+//!    (at your option) any later version.
+//!
+//!    This program is distributed in the hope that it will be useful,
+//!    but WITHOUT ANY WARRANTY; without even the implied warranty of
+//!    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//!    GNU Affero General Public License for more details.
+//!
+//!    You should have received a copy of the GNU Affero General Public License
+//!    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use ring::hmac;
+use serde::Deserialize;
+
+use crate::utils::{check_auth_date, data_check_string, decode_hex};
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebAppInitData {
+  pub query_id: Option<String>,
+  pub user: Option<WebAppUser>,
+  pub receiver: Option<WebAppUser>,
+  pub chat: Option<WebAppChat>,
+  pub chat_type: Option<String>,
+  pub chat_instance: Option<String>,
+  pub start_param: Option<String>,
+  pub can_send_after: Option<i64>,
+  pub auth_date: i64,
+  pub hash: String,
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct WebAppUser {
+  pub id: i64,
+  pub is_bot: Option<bool>,
+  pub first_name: String,
+  pub last_name: Option<String>,
+  pub username: Option<String>,
+  pub language_code: Option<String>,
+  pub is_premium: Option<bool>,
+  pub added_to_attachment_menu: Option<bool>,
+  pub allows_write_to_pm: Option<bool>,
+  pub photo_url: Option<String>,
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct WebAppChat {
+  pub id: i64,
+  pub r#type: String,
+  pub title: String,
+  pub username: Option<String>,
+  pub photo_url: Option<String>,
+}
+
+
+/// Checks the signature and freshness of the Mini App `initData` string and parses it.
+pub fn validate_init_data(init_data: &str, bot_token: &str, max_age: Duration) -> Result<WebAppInitData> {
+  let mut fields: BTreeMap<String, String> = form_urlencoded::parse(init_data.as_bytes()).into_owned().collect();
+  let hash: String = fields.remove("hash").context("ERROR: The init data does not contain a hash")?;
+
+  let data_check_string: String = data_check_string(&fields);
+
+  let secret_key: hmac::Tag = hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, b"WebAppData"), bot_token.as_bytes());
+  let key: hmac::Key = hmac::Key::new(hmac::HMAC_SHA256, secret_key.as_ref());
+  let signature: Vec<u8> = decode_hex(&hash)?;
+
+  if hmac::verify(&key, data_check_string.as_bytes(), &signature).is_err() {
+    bail!("ERROR: The init data hash is invalid");
+  }
+
+  let auth_date: i64 = fields.get("auth_date").context("ERROR: The init data does not contain an auth date")?.parse()?;
+  check_auth_date(auth_date, max_age)?;
+
+  Ok(WebAppInitData {
+    query_id: fields.remove("query_id"),
+    user: parse_json_field(&fields, "user")?,
+    receiver: parse_json_field(&fields, "receiver")?,
+    chat: parse_json_field(&fields, "chat")?,
+    chat_type: fields.remove("chat_type"),
+    chat_instance: fields.remove("chat_instance"),
+    start_param: fields.remove("start_param"),
+    can_send_after: fields.get("can_send_after").map(|value: &String| value.parse()).transpose()?,
+    auth_date,
+    hash,
+  })
+}
+
+
+fn parse_json_field<T: for<'de> Deserialize<'de>>(fields: &BTreeMap<String, String>, name: &str) -> Result<Option<T>> {
+  match fields.get(name) {
+    Some(value) => Ok(Some(serde_json::from_str(value).with_context(|| format!("ERROR: Couldn't parse the '{name}' field of the init data"))?)),
+    None => Ok(None),
+  }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+
+  const BOT_TOKEN: &str = "123456:ABC-DEF1234ghIkl-zyx57W2v1u123ew11";
+
+  const INIT_DATA: &str = "query_id=AAHdF6IQAAAAAN0XohDhrOrc&user=%7B%22id%22%3A279058397%2C%22first_name%22%3A%22Vladislav%22%2C%22username%22%3A%22vdkfrost%22%2C%22language_code%22%3A%22ru%22%7D&auth_date=1700000000&hash=687e2917c979de2a1c889640c10208752079f9c657fba87dab2091664ec91530";
+
+  const HASH: &str = "687e2917c979de2a1c889640c10208752079f9c657fba87dab2091664ec91530";
+
+  const NO_MAX_AGE: Duration = Duration::MAX;
+
+
+  #[test]
+  fn valid_init_data() {
+    let init_data: WebAppInitData = validate_init_data(INIT_DATA, BOT_TOKEN, NO_MAX_AGE).unwrap();
+    assert_eq!(init_data.query_id.as_deref(), Some("AAHdF6IQAAAAAN0XohDhrOrc"));
+    assert_eq!(init_data.user.map(|user: WebAppUser| user.id), Some(279058397));
+    assert_eq!(init_data.auth_date, 1700000000);
+    assert_eq!(init_data.hash, HASH);
+  }
+
+
+  #[test]
+  fn wrong_bot_token() {
+    assert!(validate_init_data(INIT_DATA, "654321:ABC-DEF1234ghIkl-zyx57W2v1u123ew11", NO_MAX_AGE).is_err());
+  }
+
+
+  #[test]
+  fn tampered_field() {
+    let init_data: String = INIT_DATA.replace("query_id=AAH", "query_id=BAH");
+    assert!(validate_init_data(&init_data, BOT_TOKEN, NO_MAX_AGE).is_err());
+  }
+
+
+  #[test]
+  fn tampered_hash() {
+    let init_data: String = INIT_DATA.replace(HASH, &HASH.replace("687e", "687f"));
+    assert!(validate_init_data(&init_data, BOT_TOKEN, NO_MAX_AGE).is_err());
+  }
+
+
+  #[test]
+  fn malformed_hash() {
+    for hash in ["", "687", "zz7e", "+f7e"] {
+      let init_data: String = INIT_DATA.replace(HASH, hash);
+      assert!(validate_init_data(&init_data, BOT_TOKEN, NO_MAX_AGE).is_err(), "{hash}");
+    }
+  }
+
+
+  #[test]
+  fn missing_hash() {
+    let init_data: String = INIT_DATA.replace(&format!("&hash={HASH}"), "");
+    assert!(validate_init_data(&init_data, BOT_TOKEN, NO_MAX_AGE).is_err());
+  }
+
+
+  #[test]
+  fn outdated_init_data() {
+    assert!(validate_init_data(INIT_DATA, BOT_TOKEN, Duration::from_secs(60)).is_err());
+  }
+
+
+  #[test]
+  fn malformed_user_with_valid_hash() {
+    let init_data: &str = "query_id=AAHdF6IQAAAAAN0XohDhrOrc&user=%7B%22id%22%3A%22oops%22%7D&auth_date=1700000000&hash=a290b1694e8ebb89529363848edaf3bba2e5a24b39ac71441355a84017c6500e";
+    assert!(validate_init_data(init_data, BOT_TOKEN, NO_MAX_AGE).is_err());
+  }
+}
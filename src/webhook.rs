@@ -0,0 +1,80 @@
+//!    Rust telegram bot api. The library provides asynchronous access to the telegram bot api.
+//!    Copyright (C) 2024  Andrew Kozmin
+//!
+//!    This program is free software: you can redistribute it and/or modify
+//!    it under the terms of the GNU Affero General Public License as published by
+//!    the Free Software Foundation, either version 3 of the License, or
+//!    (at your option) any later version.
+//!
+//!    This program is distributed in the hope that it will be useful,
+//!    but WITHOUT ANY WARRANTY; without even the implied warranty of
+//!    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//!    GNU Affero General Public License for more details.
+//!
+//!    You should have received a copy of the GNU Affero General Public License
+//!    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+
+use std::net::{IpAddr, Ipv4Addr};
+
+
+pub const TELEGRAM_SUBNETS: [(Ipv4Addr, u8); 2] = [
+  (Ipv4Addr::new(149, 154, 160, 0), 20),
+  (Ipv4Addr::new(91, 108, 4, 0), 22),
+];
+
+
+/// Checks whether a webhook request comes from one of the subnets Telegram sends updates from.
+pub fn is_telegram_ip(ip: IpAddr) -> bool {
+  let ip: Ipv4Addr = match ip {
+    IpAddr::V4(ip) => ip,
+    IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+      Some(ip) => ip,
+      None => return false,
+    },
+  };
+
+  TELEGRAM_SUBNETS.iter().any(|(network, prefix): &(Ipv4Addr, u8)| {
+    let mask: u32 = u32::MAX << (32 - prefix);
+    u32::from(ip) & mask == u32::from(*network) & mask
+  })
+}
+
+
+#[cfg(test)]
+mod tests {
+  use std::net::Ipv6Addr;
+
+  use super::*;
+
+
+  fn is_telegram_ipv4(a: u8, b: u8, c: u8, d: u8) -> bool {
+    is_telegram_ip(IpAddr::V4(Ipv4Addr::new(a, b, c, d)))
+  }
+
+
+  #[test]
+  fn first_subnet_bounds() {
+    assert!(is_telegram_ipv4(149, 154, 160, 0));
+    assert!(is_telegram_ipv4(149, 154, 175, 255));
+    assert!(!is_telegram_ipv4(149, 154, 159, 255));
+    assert!(!is_telegram_ipv4(149, 154, 176, 0));
+  }
+
+
+  #[test]
+  fn second_subnet_bounds() {
+    assert!(is_telegram_ipv4(91, 108, 4, 0));
+    assert!(is_telegram_ipv4(91, 108, 7, 255));
+    assert!(!is_telegram_ipv4(91, 108, 3, 255));
+    assert!(!is_telegram_ipv4(91, 108, 8, 0));
+  }
+
+
+  #[test]
+  fn ipv4_mapped_ipv6() {
+    assert!(is_telegram_ip(IpAddr::V6(Ipv4Addr::new(149, 154, 167, 220).to_ipv6_mapped())));
+    assert!(!is_telegram_ip(IpAddr::V6(Ipv4Addr::new(127, 0, 0, 1).to_ipv6_mapped())));
+    assert!(!is_telegram_ip(IpAddr::V6(Ipv6Addr::new(0x2001, 0x67c, 0x4e8, 0, 0, 0, 0, 1))));
+  }
+}